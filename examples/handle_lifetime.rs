@@ -16,6 +16,7 @@ impl<'a> A<'a> {
 }
 
 impl<'a, 'b> B<'a, 'b> where 'a: 'b {
+    #[allow(clippy::redundant_closure_call)]
     fn call(&mut self) {
         (|| {
             self.a.c().call();
@@ -1,49 +1,50 @@
 use crate::{KsFc};
-use crate::format::{parse, into_bin};
+use crate::format::{parse};
 
 
 // Basic commands
 impl KsFc {
     /// `ABORt`
     pub fn abort(&mut self) -> crate::Result<()> {
-        self.send(b"ABOR")
+        self.command("ABOR")
     }
 
     /// `AUToscale`
     pub fn autoscale(&mut self) -> crate::Result<()> {
-        self.send(b"AUT")
+        self.command("AUT")
     }
 
     /// `FETCh?`
     pub fn fetch(&mut self) -> crate::Result<f64> {
-        self.send(b"FETC?").and_then(|()| self.receive_value())
+        self.idempotent(|fc| fc.query_value("FETC?"))
     }
 
     /// `INITiate`
+    ///
+    /// Trigger count × sample count is checked against `MAX_READINGS` first.
     pub fn initiate(&mut self) -> crate::Result<()> {
-        self.send(b"INIT")
-        .and_then(|()| self.system_error())
-        .and_then(|e| match e {
-            Some(e) => Err(e.into()),
-            None => Ok(()),
-        })
+        self.checked_reading_count("INIT")?;
+        self.command("INIT")
     }
 
     /// `READ?`
     pub fn read(&mut self) -> crate::Result<f64> {
-        self.send(b"READ?").and_then(|()| self.receive_value())
+        self.query_value("READ?")
     }
 
     /// `R? <N>`
+    ///
+    /// Removes returned readings from reading memory, empty when there are none yet.
     pub fn r(&mut self, max_count: Option<usize>) -> crate::Result<Vec<f64>> {
-        match max_count {
-            Some(n) => self.send(format!("R? {}", n).as_bytes()),
-            None => self.send(b"R?"),
-        }
-        .and_then(|()| self.receive())
-        .and_then(|data| into_bin(data))
-        .and_then(|buf| String::from_utf8(buf).map_err(|_| "bad utf8 sequence".into()))
-        .and_then(|text| -> crate::Result<Vec<f64>> {
+        let cmd = match max_count {
+            Some(n) => format!("R? {}", n),
+            None => String::from("R?"),
+        };
+        self.query_bin(&cmd, None, |buf| {
+            let text = std::str::from_utf8(buf).map_err(|_| crate::Error::from("bad utf8 sequence"))?;
+            if text.trim().is_empty() {
+                return Ok(Vec::new());
+            }
             text.split(',')
             .map(|s| parse::<f64>(s).map_err(|e| e.into()) )
             .collect()
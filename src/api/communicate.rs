@@ -0,0 +1,49 @@
+use crate::{
+    KsFc, KsDevErr,
+    deverr::{KsDevErrVariant, RangeKind},
+    types::{Interface},
+    constants::{GPIB_ADDR_MAX},
+    format::{parse, parse_bool, parse_string, bool_as_str},
+};
+
+
+// Remote interfaces
+impl KsFc {
+    /// `SYSTem:COMMunicate:ENABle <state>, <interface>`
+    ///
+    /// Takes effect after power cycle. Disabling the interface this
+    /// connection uses locks the host out.
+    pub fn system_communicate_enable_set(&mut self, interface: Interface, state: bool) -> crate::Result<()> {
+        self.command(&format!("SYST:COMM:ENAB {},{}", bool_as_str(state), interface.mnemonic()))
+    }
+    /// `SYSTem:COMMunicate:ENABle? <interface>`
+    pub fn system_communicate_enable_get(&mut self, interface: Interface) -> crate::Result<bool> {
+        let cmd = format!("SYST:COMM:ENAB? {}", interface.mnemonic());
+        self.idempotent(|fc| fc.query(&cmd, |text| parse_bool(text).map_err(|e| e.into())))
+    }
+
+    /// `SYSTem:COMMunicate:GPIB:ADDRess <address>`, 0 to `GPIB_ADDR_MAX`
+    pub fn system_communicate_gpib_address_set(&mut self, address: u8) -> crate::Result<()> {
+        let cmd = format!("SYST:COMM:GPIB:ADDR {}", address);
+        if address > GPIB_ADDR_MAX {
+            return Err(Self::rejected(&cmd, crate::Error::Dev(KsDevErr::from(
+                KsDevErrVariant::DataOutOfRange(RangeKind::Generic)
+            ))));
+        }
+        self.command(&cmd)
+    }
+    /// `SYSTem:COMMunicate:GPIB:ADDRess?`
+    pub fn system_communicate_gpib_address_get(&mut self) -> crate::Result<u8> {
+        self.idempotent(|fc| fc.query("SYST:COMM:GPIB:ADDR?", |text| parse::<u8>(text.trim()).map_err(|e| e.into())))
+    }
+
+    /// `SYSTem:COMMunicate:LAN:MAC?`
+    pub fn system_communicate_lan_mac(&mut self) -> crate::Result<String> {
+        self.idempotent(|fc| fc.query("SYST:COMM:LAN:MAC?", |text| parse_string(text).map_err(|e| e.into())))
+    }
+
+    /// `SYSTem:COMMunicate:LAN:DOMain?`, assigned by DHCP
+    pub fn system_communicate_lan_domain(&mut self) -> crate::Result<String> {
+        self.idempotent(|fc| fc.query("SYST:COMM:LAN:DOM?", |text| parse_string(text).map_err(|e| e.into())))
+    }
+}
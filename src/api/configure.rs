@@ -7,6 +7,6 @@ use crate::{
 impl KsFc {
     /// `CONFigure:FREQuency @<channel>`
     pub fn configure_frequency(&mut self, cn: ChannelNo) -> crate::Result<()> {
-        self.send(format!("CONF:FREQ (@{})", cn as u8).as_bytes())
+        self.command(&format!("CONF:FREQ (@{})", cn as u8))
     }
 }
@@ -0,0 +1,117 @@
+use crate::{
+    KsFc, KsDevErr,
+    deverr::{KsDevErrVariant, RangeKind},
+    types::{DisplayMode},
+    constants::{DISPLAY_TEXT_MAX_LEN, DISPLAY_DIGITS_MIN, DISPLAY_DIGITS_MAX},
+    format::{parse, parse_bool, parse_string, bool_as_str, quote_string},
+};
+
+
+/// Replaces typographic punctuation with its ASCII counterpart.
+fn normalize_display_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2212}' => out.push('-'),
+            '\u{2018}' | '\u{2019}' => out.push('\''),
+            '\u{201C}' | '\u{201D}' => out.push('"'),
+            '\u{2026}' => out.push_str("..."),
+            '\u{00A0}' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Checks display message before sending, device rejects it with -144 or -151.
+fn check_display_text(text: &str) -> crate::Result<()> {
+    if !text.chars().all(|c| c.is_ascii() && !c.is_ascii_control()) {
+        Err(crate::Error::Dev(KsDevErr::from(KsDevErrVariant::InvalidString)))
+    } else if text.len() > DISPLAY_TEXT_MAX_LEN {
+        Err(crate::Error::Dev(KsDevErr::from(KsDevErrVariant::CharacterDataTooLong)))
+    } else {
+        Ok(())
+    }
+}
+
+// Front panel display
+impl KsFc {
+    /// `DISPlay:STATe <state>`, disabling display speeds up measurements
+    pub fn display_state_set(&mut self, state: bool) -> crate::Result<()> {
+        self.command(&format!("DISP:STAT {}", bool_as_str(state)))
+    }
+    /// `DISPlay:STATe?`
+    pub fn display_state_get(&mut self) -> crate::Result<bool> {
+        self.idempotent(|fc| fc.query("DISP:STAT?", |text| parse_bool(text).map_err(|e| e.into())))
+    }
+
+    /// `DISPlay:MODE <mode>`
+    pub fn display_mode_set(&mut self, mode: DisplayMode) -> crate::Result<()> {
+        self.command(&format!("DISP:MODE {}", mode.mnemonic()))
+    }
+    /// `DISPlay:MODE?`
+    pub fn display_mode_get(&mut self) -> crate::Result<DisplayMode> {
+        self.idempotent(|fc| fc.query("DISP:MODE?", |text| text.parse::<DisplayMode>().map_err(|e| e.into())))
+    }
+
+    /// `DISPlay:TEXT <text>`
+    ///
+    /// Dashes, quotes, ellipsis and no-break space are replaced with ASCII,
+    /// the result must be printable ASCII of at most `DISPLAY_TEXT_MAX_LEN` characters.
+    pub fn display_text_set(&mut self, text: &str) -> crate::Result<()> {
+        let text = normalize_display_text(text);
+        let cmd = format!("DISP:TEXT {}", quote_string(&text));
+        check_display_text(&text).map_err(|e| Self::rejected(&cmd, e))?;
+        self.command(&cmd)
+    }
+    /// `DISPlay:TEXT?`
+    pub fn display_text_get(&mut self) -> crate::Result<String> {
+        self.idempotent(|fc| fc.query("DISP:TEXT?", |text| parse_string(text).map_err(|e| e.into())))
+    }
+    /// `DISPlay:TEXT:CLEar`
+    pub fn display_text_clear(&mut self) -> crate::Result<()> {
+        self.command("DISP:TEXT:CLE")
+    }
+
+    /// `DISPlay:DIGit:MASK <digits>`, `DISPLAY_DIGITS_MIN` to `DISPLAY_DIGITS_MAX`
+    pub fn display_digit_mask_set(&mut self, digits: u8) -> crate::Result<()> {
+        let cmd = format!("DISP:DIG:MASK {}", digits);
+        if !(DISPLAY_DIGITS_MIN..=DISPLAY_DIGITS_MAX).contains(&digits) {
+            return Err(Self::rejected(&cmd, crate::Error::Dev(KsDevErr::from(
+                KsDevErrVariant::DataOutOfRange(RangeKind::Generic)
+            ))));
+        }
+        self.command(&cmd)
+    }
+    /// `DISPlay:DIGit:MASK?`
+    pub fn display_digit_mask_get(&mut self) -> crate::Result<u8> {
+        self.idempotent(|fc| fc.query("DISP:DIG:MASK?", |text| parse::<u8>(text.trim()).map_err(|e| e.into())))
+    }
+
+    /// `DISPlay:DIGit:MASK:AUTO <state>`
+    pub fn display_digit_mask_auto_set(&mut self, state: bool) -> crate::Result<()> {
+        self.command(&format!("DISP:DIG:MASK:AUTO {}", bool_as_str(state)))
+    }
+    /// `DISPlay:DIGit:MASK:AUTO?`
+    pub fn display_digit_mask_auto_get(&mut self) -> crate::Result<bool> {
+        self.idempotent(|fc| fc.query("DISP:DIG:MASK:AUTO?", |text| parse_bool(text).map_err(|e| e.into())))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_text() {
+        let text = normalize_display_text("RUN 42 IN PROGRESS \u{2014} DO NOT TOUCH");
+        assert_eq!(text, "RUN 42 IN PROGRESS - DO NOT TOUCH");
+        assert!(check_display_text(&text).is_ok());
+        assert_eq!(normalize_display_text("\u{201C}Don\u{2019}t\u{201D}\u{2026}"), "\"Don't\"...");
+        let e = check_display_text(&normalize_display_text("Caf\u{E9}")).unwrap_err();
+        assert_eq!(e.dev().unwrap().variant(), Some(KsDevErrVariant::InvalidString));
+        let e = check_display_text(&"X".repeat(DISPLAY_TEXT_MAX_LEN + 1)).unwrap_err();
+        assert_eq!(e.dev().unwrap().code(), -144);
+    }
+}
@@ -0,0 +1,74 @@
+use std::time::{Instant};
+
+use crate::{
+    KsFc,
+    types::{ImageFormat},
+    constants::{SCREENSHOT_TIMEOUT},
+};
+
+
+/// Checks that `image` is a whole image file of `format`.
+///
+/// PNG must end with `IEND` chunk, BMP size field must match the data length.
+fn check_image(format: ImageFormat, image: &[u8]) -> crate::Result<()> {
+    if !image.starts_with(format.signature()) {
+        return Err(format!("Screen capture is not a {} image", format.mnemonic()).into());
+    }
+    let complete = match format {
+        ImageFormat::Png => image.ends_with(b"IEND\xae\x42\x60\x82"),
+        ImageFormat::Bmp => image.get(2..6).is_some_and(|size| {
+            u32::from_le_bytes([size[0], size[1], size[2], size[3]]) as usize == image.len()
+        }),
+    };
+    if !complete {
+        return Err(format!("Screen capture {} image is truncated", format.mnemonic()).into());
+    }
+    Ok(())
+}
+
+
+// Hard copy
+impl KsFc {
+    /// `HCOPy:SDUMp:DATA:FORMat <format>`
+    pub fn hcopy_sdump_data_format_set(&mut self, format: ImageFormat) -> crate::Result<()> {
+        self.command(&format!("HCOP:SDUM:DATA:FORM {}", format.mnemonic()))
+    }
+    /// `HCOPy:SDUMp:DATA:FORMat?`
+    pub fn hcopy_sdump_data_format_get(&mut self) -> crate::Result<ImageFormat> {
+        self.idempotent(|fc| fc.query("HCOP:SDUM:DATA:FORM?", |text| text.parse::<ImageFormat>().map_err(|e| e.into())))
+    }
+
+    /// `HCOPy:SDUMp:DATA?`, image of the display in the selected format.
+    pub fn hcopy_sdump_data(&mut self) -> crate::Result<Vec<u8>> {
+        self.idempotent(|fc| fc.query_bin("HCOP:SDUM:DATA?", Some(SCREENSHOT_TIMEOUT), |data| Ok(data.to_vec())))
+    }
+
+    /// Captures the display as image file contents.
+    pub fn screenshot(&mut self, format: ImageFormat) -> crate::Result<Vec<u8>> {
+        self.hcopy_sdump_data_format_set(format)?;
+        let start = Instant::now();
+        let image = self.hcopy_sdump_data()?;
+        check_image(format, &image)
+        .map_err(|e| e.with_context(Self::context("HCOP:SDUM:DATA?", start, None)))?;
+        Ok(image)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image() {
+        let mut bmp = b"BM\x0a\0\0\0\0\0\0\0".to_vec();
+        assert!(check_image(ImageFormat::Bmp, &bmp).is_ok());
+        bmp.pop();
+        assert!(check_image(ImageFormat::Bmp, &bmp).is_err());
+        assert!(check_image(ImageFormat::Png, &bmp).is_err());
+
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\0IEND\xae\x42\x60\x82";
+        assert!(check_image(ImageFormat::Png, png).is_ok());
+        assert!(check_image(ImageFormat::Png, &png[..png.len() - 1]).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::format::{parse, into_text};
+use crate::format::{parse};
 use crate::types::*;
 use crate::constants::*;
 use crate::{KsFc};
@@ -8,41 +8,78 @@ use crate::{KsFc};
 impl KsFc {
     /// `*CAL?`
     pub fn cal(&mut self) -> crate::Result<bool> {
-        self.send(b"*CAL?")
-        .and_then(|()| self.receive_timeout(CAL_TIMEOUT))
-        .and_then(|data| into_text(data))
-        .and_then(|text| parse::<i32>(&text).map_err(|e| e.into()))
+        self.query_timeout("*CAL?", Some(CAL_TIMEOUT), |text| {
+            parse::<i32>(text).map_err(|e| e.into())
+        })
         .map(|t| t == 0)
     }
 
     /// `*CLS`
     pub fn cls(&mut self) -> crate::Result<()> {
-        self.send(b"*CLS")
+        self.command("*CLS")
     }
 
     /// `*RST`
     pub fn rst(&mut self) -> crate::Result<()> {
-        self.send(b"*RST")
+        self.command("*RST")
     }
 
     /// `*IDN?`
     pub fn idn(&mut self) -> crate::Result<String> {
-        self.send(b"*IDN?")
-        .and_then(|_| self.receive())
-        .and_then(|data| into_text(data))
+        self.idempotent(|fc| fc.query("*IDN?", |text| Ok(String::from(text))))
+    }
+
+    /// `*IDN?` parsed into fields
+    pub fn identity(&mut self) -> crate::Result<Identity> {
+        self.idempotent(|fc| fc.query("*IDN?", |text| text.parse::<Identity>().map_err(|e| e.into())))
     }
 
     /// `*ESE?`
     pub fn ese_get(&mut self) -> crate::Result<EventReg> {
-        self.send(b"*ESE?")
-        .and_then(|()| self.receive())
-        .and_then(|data| into_text(data))
-        .and_then(|buf| parse!(&buf, u8).map_err(|e| e.into()))
+        self.idempotent(|fc| fc.query("*ESE?", |text| parse!(text, u8).map_err(|e| e.into())))
         .map(|b| EventReg::from_bits_truncate(b.0))
     }
 
     /// `*ESE <bits>`
     pub fn ese_set(&mut self, ereg: EventReg) -> crate::Result<()> {
-        self.send(format!("*ESE {}", ereg.bits()).as_bytes())
+        self.command(&format!("*ESE {}", ereg.bits()))
+    }
+
+    /// `*OPC`
+    pub fn opc(&mut self) -> crate::Result<()> {
+        self.command("*OPC")
+    }
+
+    /// `*ESR?`
+    pub fn esr_get(&mut self) -> crate::Result<EventReg> {
+        self.query("*ESR?", |text| parse!(text, u8).map_err(|e| e.into()))
+        .map(|b| EventReg::from_bits_truncate(b.0))
+    }
+
+    /// `*SRE?`
+    pub fn sre_get(&mut self) -> crate::Result<StatusByte> {
+        self.idempotent(|fc| fc.query("*SRE?", |text| parse!(text, u8).map_err(|e| e.into())))
+        .map(|b| StatusByte::from_bits_truncate(b.0))
+    }
+
+    /// `*SRE <bits>`
+    pub fn sre_set(&mut self, sreg: StatusByte) -> crate::Result<()> {
+        self.command(&format!("*SRE {}", sreg.bits()))
+    }
+
+    /// `*STB?`
+    pub fn stb_get(&mut self) -> crate::Result<StatusByte> {
+        self.idempotent(|fc| fc.query("*STB?", |text| parse!(text, u8).map_err(|e| e.into())))
+        .map(|b| StatusByte::from_bits_truncate(b.0))
+    }
+
+    /// `*SAV <slot>`
+    pub fn sav(&mut self, slot: StateSlot) -> crate::Result<()> {
+        self.command(&format!("*SAV {}", slot.number()))
+    }
+
+    /// `*RCL <slot>`
+    pub fn rcl(&mut self, slot: StateSlot) -> crate::Result<()> {
+        self.command(&format!("*RCL {}", slot.number()))
     }
-}
\ No newline at end of file
+}
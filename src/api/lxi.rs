@@ -0,0 +1,100 @@
+use std::time::{Duration, Instant};
+use std::thread::{sleep};
+
+use crate::{
+    KsFc,
+    constants::{RESTART_POLL_PERIOD},
+    format::{parse_bool, parse_string, quote_string, bool_as_str},
+};
+
+
+// LXI
+impl KsFc {
+    /// `LXI:IDENtify:STATe <state>`, blinks LXI indicator on the front panel
+    pub fn lxi_identify_set(&mut self, state: bool) -> crate::Result<()> {
+        self.command(&format!("LXI:IDEN:STAT {}", bool_as_str(state)))
+    }
+    /// `LXI:IDENtify:STATe?`
+    pub fn lxi_identify_get(&mut self) -> crate::Result<bool> {
+        self.idempotent(|fc| fc.query("LXI:IDEN:STAT?", |text| parse_bool(text).map_err(|e| e.into())))
+    }
+
+    /// Blinks LXI indicator for `duration` to find the device in a rack.
+    pub fn identify_blink(&mut self, duration: Duration) -> crate::Result<()> {
+        self.lxi_identify_set(true)?;
+        sleep(duration);
+        self.lxi_identify_set(false)
+    }
+
+    /// `LXI:MDNS:ENABle <state>`
+    pub fn lxi_mdns_enable_set(&mut self, state: bool) -> crate::Result<()> {
+        self.command(&format!("LXI:MDNS:ENAB {}", bool_as_str(state)))
+    }
+    /// `LXI:MDNS:ENABle?`
+    pub fn lxi_mdns_enable_get(&mut self) -> crate::Result<bool> {
+        self.idempotent(|fc| fc.query("LXI:MDNS:ENAB?", |text| parse_bool(text).map_err(|e| e.into())))
+    }
+
+    /// `LXI:MDNS:HNAMe:RESolved?`
+    pub fn lxi_mdns_hostname_resolved(&mut self) -> crate::Result<String> {
+        self.idempotent(|fc| fc.query("LXI:MDNS:HNAM:RES?", |text| parse_string(text).map_err(|e| e.into())))
+    }
+
+    /// `LXI:MDNS:SNAMe:DESired <name>`
+    pub fn lxi_mdns_service_name_desired_set(&mut self, name: &str) -> crate::Result<()> {
+        self.command(&format!("LXI:MDNS:SNAM:DES {}", quote_string(name)))
+    }
+    /// `LXI:MDNS:SNAMe:DESired?`
+    pub fn lxi_mdns_service_name_desired_get(&mut self) -> crate::Result<String> {
+        self.idempotent(|fc| fc.query("LXI:MDNS:SNAM:DES?", |text| parse_string(text).map_err(|e| e.into())))
+    }
+    /// `LXI:MDNS:SNAMe:RESolved?`, desired name made unique on the network
+    pub fn lxi_mdns_service_name_resolved(&mut self) -> crate::Result<String> {
+        self.idempotent(|fc| fc.query("LXI:MDNS:SNAM:RES?", |text| parse_string(text).map_err(|e| e.into())))
+    }
+
+    /// Sends command after which device drops the connection.
+    pub(crate) fn send_and_disconnect(&mut self, cmd: &str) -> crate::Result<()> {
+        let res = self.send(cmd.as_bytes());
+        self.disconnect();
+        res
+    }
+
+    /// `LXI:RESet`, resets LAN settings to defaults and restarts LAN interface.
+    ///
+    /// The device address may change, so the connection is closed and not restored.
+    pub fn lxi_reset(&mut self) -> crate::Result<()> {
+        self.send_and_disconnect("LXI:RES")
+    }
+
+    /// `LXI:RESTart`, restarts LAN interface with current settings.
+    ///
+    /// The connection is closed. With `reconnect` set, connection attempts are
+    /// made every `RESTART_POLL_PERIOD` until the device answers `*IDN?` or the
+    /// timeout expires, then `Error::WaitTimeout` is returned, which is not
+    /// retryable as the restart has already been done.
+    pub fn lxi_restart(&mut self, reconnect: Option<Duration>) -> crate::Result<()> {
+        self.send_and_disconnect("LXI:REST")?;
+        let timeout = match reconnect {
+            Some(t) => t,
+            None => return Ok(()),
+        };
+        let start = Instant::now();
+        loop {
+            sleep(RESTART_POLL_PERIOD);
+            // Socket may accept before the instrument serves commands again.
+            if self.connect().is_ok() && self.idn().is_ok() {
+                break Ok(());
+            }
+            self.disconnect();
+            let elapsed = start.elapsed();
+            if elapsed >= timeout {
+                let err = crate::Error::WaitTimeout {
+                    event: String::from("LXI restart"),
+                    waited: elapsed,
+                };
+                break Err(err.with_context(Self::context("LXI:REST", start, None)));
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{
+    KsFc, KsDevErr,
+    deverr::{KsDevErrVariant, ConflictKind},
+    types::{StateSlot},
+    format::{parse, parse_bool, bool_as_str},
+};
+
+
+fn auto_recall_conflict() -> crate::Error {
+    crate::Error::Dev(KsDevErr::from(
+        KsDevErrVariant::SettingsConflict(ConflictKind::DeleteAutoRecallState)
+    ))
+}
+
+// State storage
+impl KsFc {
+    /// `MEMory:NSTates?`
+    pub fn memory_nstates(&mut self) -> crate::Result<usize> {
+        self.idempotent(|fc| fc.query("MEM:NST?", |text| parse::<usize>(text).map_err(|e| e.into())))
+    }
+
+    /// `MEMory:STATe:DELete <slot>`
+    ///
+    /// The state selected for power-on recall can't be deleted while
+    /// auto-recall is enabled.
+    pub fn memory_state_delete(&mut self, slot: StateSlot) -> crate::Result<()> {
+        let cmd = format!("MEM:STAT:DEL {}", slot.number());
+        if self.memory_state_recall_auto_get()? && self.memory_state_recall_select_get()? == slot {
+            return Err(Self::rejected(&cmd, auto_recall_conflict()));
+        }
+        self.command(&cmd)
+    }
+
+    /// `MEMory:STATe:DELete:ALL`
+    ///
+    /// Refused while auto-recall is enabled, as it would delete the selected state.
+    pub fn memory_state_delete_all(&mut self) -> crate::Result<()> {
+        if self.memory_state_recall_auto_get()? {
+            return Err(Self::rejected("MEM:STAT:DEL:ALL", auto_recall_conflict()));
+        }
+        self.command("MEM:STAT:DEL:ALL")
+    }
+
+    /// `MEMory:STATe:RECall:AUTO <state>`
+    pub fn memory_state_recall_auto_set(&mut self, state: bool) -> crate::Result<()> {
+        self.command(&format!("MEM:STAT:REC:AUTO {}", bool_as_str(state)))
+    }
+    /// `MEMory:STATe:RECall:AUTO?`
+    pub fn memory_state_recall_auto_get(&mut self) -> crate::Result<bool> {
+        self.idempotent(|fc| fc.query("MEM:STAT:REC:AUTO?", |text| parse_bool(text).map_err(|e| e.into())))
+    }
+
+    /// `MEMory:STATe:RECall:SELect <slot>`
+    pub fn memory_state_recall_select_set(&mut self, slot: StateSlot) -> crate::Result<()> {
+        self.command(&format!("MEM:STAT:REC:SEL {}", slot.number()))
+    }
+    /// `MEMory:STATe:RECall:SELect?`
+    pub fn memory_state_recall_select_get(&mut self) -> crate::Result<StateSlot> {
+        self.idempotent(|fc| fc.query("MEM:STAT:REC:SEL?", |text| text.parse::<StateSlot>().map_err(|e| e.into())))
+    }
+
+    /// `MEMory:STATe:VALid? <slot>`, whether a state is stored in the slot
+    pub fn memory_state_valid(&mut self, slot: StateSlot) -> crate::Result<bool> {
+        let cmd = format!("MEM:STAT:VAL? {}", slot.number());
+        self.idempotent(|fc| fc.query(&cmd, |text| parse_bool(text).map_err(|e| e.into())))
+    }
+}
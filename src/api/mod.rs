@@ -4,36 +4,40 @@ pub mod configure;
 pub mod sense;
 pub mod system;
 pub mod trigger;
+pub mod sample;
+pub mod output;
+pub mod memory;
+pub mod hcopy;
+pub mod display;
+pub mod communicate;
+pub mod lxi;
+pub mod status;
 
 
-use crate::format::{into_text, parse};
+use std::time::{Instant};
+
+use crate::format::{parse};
 use crate::constants::*;
 use crate::{KsFc};
 
 
 impl KsFc {
-    fn receive_value(&mut self) -> crate::Result<f64> {
-        self.receive()
-        .and_then(|data| into_text(data))
-        .and_then(|text| {
-            parse::<f64>(&text).map_err(|e| e.into())
-            .map(|v| {
-                if v >= ERROR_VALUE {
-                    None
-                } else {
-                    Some(v)
-                }
-            })
-        })
+    /// Sends measurement query and receives its value.
+    ///
+    /// The device returns `ERROR_VALUE` if measurement failed,
+    /// then the error is taken from the error queue.
+    fn query_value(&mut self, cmd: &str) -> crate::Result<f64> {
+        let start = Instant::now();
+        self.query(cmd, |text| parse::<f64>(text).map_err(|e| e.into()))
         .and_then(|v| {
-            match v {
-                Some(v) => Ok(v),
-                None => {
-                    self.system_error().and_then(|e| match e {
-                        Some(e) => Err(e.into()),
-                        None => Err(format!("Request failed but there is no errors").into()),
-                    })
-                },
+            if v < ERROR_VALUE {
+                Ok(v)
+            } else {
+                self.system_error().and_then(|e| match e {
+                    Some(e) => Err(e.into()),
+                    None => Err("Request failed but there is no errors".into()),
+                })
+                .map_err(|e| e.with_context(Self::context(cmd, start, None)))
             }
         })
     }
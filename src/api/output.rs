@@ -0,0 +1,49 @@
+use crate::{
+    KsFc,
+    setup::{GateOutput},
+    types::{Polarity},
+    format::{parse_bool, bool_as_str},
+};
+
+
+// Gate output
+impl KsFc {
+    /// `OUTPut:STATe <state>`
+    ///
+    /// Gate output can't be used with external gating of any measurement,
+    /// the device disables it and reports -221 `ConflictKind::ExtGateOutput`.
+    /// The error is only returned when errors are checked, with
+    /// `ErrorCheck::Never` it stays in the queue. `apply` checks the
+    /// conflict before sending the setup.
+    pub fn output_state_set(&mut self, state: bool) -> crate::Result<()> {
+        self.command(&format!("OUTP:STAT {}", bool_as_str(state)))
+    }
+    /// `OUTPut:STATe?`
+    pub fn output_state_get(&mut self) -> crate::Result<bool> {
+        self.idempotent(|fc| fc.query("OUTP:STAT?", |text| parse_bool(text).map_err(|e| e.into())))
+    }
+
+    /// `OUTPut:POLarity <polarity>`
+    pub fn output_polarity_set(&mut self, polarity: Polarity) -> crate::Result<()> {
+        self.command(&format!("OUTP:POL {}", polarity.mnemonic()))
+    }
+    /// `OUTPut:POLarity?`
+    pub fn output_polarity_get(&mut self) -> crate::Result<Polarity> {
+        self.idempotent(|fc| fc.query("OUTP:POL?", |text| text.parse::<Polarity>().map_err(|e| e.into())))
+    }
+
+    /// Sets polarity, then state of gate output.
+    ///
+    /// See `output_state_set` for the conflict with external gating.
+    pub fn gate_output_set(&mut self, output: &GateOutput) -> crate::Result<()> {
+        self.output_polarity_set(output.polarity)?;
+        self.output_state_set(output.enabled)
+    }
+    /// Reads gate output state and polarity.
+    pub fn gate_output_get(&mut self) -> crate::Result<GateOutput> {
+        Ok(GateOutput {
+            enabled: self.output_state_get()?,
+            polarity: self.output_polarity_get()?,
+        })
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{
+    KsFc, KsDevErr,
+    constants::{MAX_READINGS},
+    format::{parse},
+};
+
+
+/// Checks that `trigger_count` × `sample_count` readings fit reading memory.
+pub(crate) fn check_readings(trigger_count: usize, sample_count: usize) -> crate::Result<()> {
+    match trigger_count.checked_mul(sample_count) {
+        Some(n) if n <= MAX_READINGS => Ok(()),
+        _ => Err(crate::Error::Dev(KsDevErr::with_message(
+            -222,
+            &format!(
+                "Data out of range; trigger count × sample count exceeds {} readings",
+                MAX_READINGS,
+            ),
+        ))),
+    }
+}
+
+impl KsFc {
+    /// `SAMPle:COUNt <count>`
+    pub fn sample_count_set(&mut self, count: usize) -> crate::Result<()> {
+        self.command(&format!("SAMP:COUN {}", count))
+    }
+    /// `SAMPle:COUNt?`
+    pub fn sample_count_get(&mut self) -> crate::Result<usize> {
+        self.idempotent(|fc| fc.query("SAMP:COUN?", |text| parse::<usize>(text).map_err(|e| e.into())))
+    }
+
+    /// Sets trigger count and sample count together.
+    ///
+    /// Their product is checked against `MAX_READINGS` first. Separate setters
+    /// don't check it, `initiate` and `stream` do.
+    pub fn reading_count_set(&mut self, trigger_count: usize, sample_count: usize) -> crate::Result<()> {
+        check_readings(trigger_count, sample_count)
+        .map_err(|e| Self::rejected(&format!("SAMP:COUN {}", sample_count), e))?;
+        let mut batch = self.batch();
+        batch.trigger_count_set(trigger_count);
+        batch.command(&format!("SAMP:COUN {}", sample_count));
+        batch.send().map(|_| ())
+    }
+
+    /// Total number of readings taken by measurement, trigger count × sample count.
+    pub fn reading_count(&mut self) -> crate::Result<usize> {
+        let trigger_count = self.trigger_count_get()?;
+        let sample_count = self.sample_count_get()?;
+        trigger_count.checked_mul(sample_count).ok_or_else(|| Self::rejected("SAMP:COUN?", format!(
+            "Reading count {} × {} overflows", trigger_count, sample_count,
+        ).into()))
+    }
+
+    /// Reading count checked against `MAX_READINGS` before `cmd` starts measurement.
+    pub(crate) fn checked_reading_count(&mut self, cmd: &str) -> crate::Result<usize> {
+        let trigger_count = self.trigger_count_get()?;
+        let sample_count = self.sample_count_get()?;
+        check_readings(trigger_count, sample_count).map_err(|e| Self::rejected(cmd, e))?;
+        Ok(trigger_count*sample_count)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readings_limit() {
+        assert!(check_readings(1000, 1000).is_ok());
+        let e = check_readings(1000, 1001).unwrap_err();
+        assert_eq!(e.dev().unwrap().code(), -222);
+        assert!(check_readings(usize::MAX, 2).is_err());
+    }
+}
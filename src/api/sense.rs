@@ -2,7 +2,8 @@ use std::time::Duration;
 
 use crate::{
     KsFc,
-    format::{parse, into_text, secs_as_dur, dur_as_secs}
+    types::{GateSource},
+    format::{parse, secs_as_dur, dur_as_secs}
 };
 
 
@@ -10,23 +11,25 @@ use crate::{
 impl KsFc {
     /// `SENSe:FREQuency:GATE:TIME <time>`
     pub fn sense_frequency_gate_time_set(&mut self, time: Duration) -> crate::Result<()> {
-        self.send(format!("FREQ:GATE:TIME {}", dur_as_secs(time)).as_bytes())
-        .and_then(|()| self.system_error())
-        .and_then(|e| match e {
-            Some(e) => Err(e.into()),
-            None => Ok(()),
-        })
+        self.command(&format!("FREQ:GATE:TIME {}", dur_as_secs(time)))
     }
 
     /// `SENSe:FREQuency:GATE:TIME?`
     pub fn sense_frequency_gate_time_get(&mut self) -> crate::Result<Duration> {
-        self.send(b"FREQ:GATE:TIME?")
-        .and_then(|()| self.receive())
-        .and_then(|data| into_text(data))
-        .and_then(|text| {
-            parse::<f64>(&text)
+        self.idempotent(|fc| fc.query("FREQ:GATE:TIME?", |text| {
+            parse::<f64>(text)
             .map(|s| secs_as_dur(s).unwrap())
             .map_err(|e| e.into())
-        })
+        }))
+    }
+
+    /// `SENSe:FREQuency:GATE:SOURce <source>`
+    pub fn sense_frequency_gate_source_set(&mut self, source: GateSource) -> crate::Result<()> {
+        self.command(&format!("FREQ:GATE:SOUR {}", source.mnemonic()))
+    }
+
+    /// `SENSe:FREQuency:GATE:SOURce?`
+    pub fn sense_frequency_gate_source_get(&mut self) -> crate::Result<GateSource> {
+        self.idempotent(|fc| fc.query("FREQ:GATE:SOUR?", |text| text.parse::<GateSource>().map_err(|e| e.into())))
     }
 }
@@ -0,0 +1,93 @@
+use std::time::{Duration, Instant};
+use std::thread::{sleep};
+
+use crate::{KsFc};
+use crate::types::*;
+use crate::constants::*;
+
+
+// Status reporting
+impl KsFc {
+    /// Polls `*STB?` until any of `mask` bits is set or `timeout` expires.
+    ///
+    /// Returns the status byte that matched the mask.
+    /// The transport has no SRQ support, so the status byte is polled
+    /// every `STB_POLL_PERIOD`. Use `sre_set`, `ese_set` and the status
+    /// register enables to route the events of interest to the status byte.
+    ///
+    /// Fails with `Error::WaitTimeout` if the event is not seen in time.
+    pub fn wait_event(&mut self, mask: StatusByte, timeout: Duration) -> crate::Result<StatusByte> {
+        let start = Instant::now();
+        loop {
+            let stb = self.stb_get()?;
+            if stb.intersects(mask) {
+                break Ok(stb);
+            }
+            let elapsed = start.elapsed();
+            if elapsed >= timeout {
+                let err = crate::Error::WaitTimeout {
+                    event: format!("Status event {:?}", mask),
+                    waited: elapsed,
+                };
+                break Err(err.with_context(Self::context("*STB?", start, None)));
+            }
+            sleep(STB_POLL_PERIOD.min(timeout - elapsed));
+        }
+    }
+
+    fn status_reg_get(&mut self, cmd: &str) -> crate::Result<u16> {
+        self.query(cmd, |text| parse!(text, u16).map_err(|e| e.into()))
+        .map(|b| b.0)
+    }
+
+    /// `STATus:OPERation:CONDition?`
+    pub fn status_operation_condition_get(&mut self) -> crate::Result<OperationReg> {
+        self.idempotent(|fc| fc.status_reg_get("STAT:OPER:COND?"))
+        .map(OperationReg::from_bits_truncate)
+    }
+
+    /// `STATus:OPERation:EVENt?`
+    pub fn status_operation_event_get(&mut self) -> crate::Result<OperationReg> {
+        self.status_reg_get("STAT:OPER:EVEN?")
+        .map(OperationReg::from_bits_truncate)
+    }
+
+    /// `STATus:OPERation:ENABle?`
+    pub fn status_operation_enable_get(&mut self) -> crate::Result<OperationReg> {
+        self.idempotent(|fc| fc.status_reg_get("STAT:OPER:ENAB?"))
+        .map(OperationReg::from_bits_truncate)
+    }
+
+    /// `STATus:OPERation:ENABle <bits>`
+    pub fn status_operation_enable_set(&mut self, oreg: OperationReg) -> crate::Result<()> {
+        self.command(&format!("STAT:OPER:ENAB {}", oreg.bits()))
+    }
+
+    /// `STATus:QUEStionable:CONDition?`
+    pub fn status_questionable_condition_get(&mut self) -> crate::Result<QuestionableReg> {
+        self.idempotent(|fc| fc.status_reg_get("STAT:QUES:COND?"))
+        .map(QuestionableReg::from_bits_truncate)
+    }
+
+    /// `STATus:QUEStionable:EVENt?`
+    pub fn status_questionable_event_get(&mut self) -> crate::Result<QuestionableReg> {
+        self.status_reg_get("STAT:QUES:EVEN?")
+        .map(QuestionableReg::from_bits_truncate)
+    }
+
+    /// `STATus:QUEStionable:ENABle?`
+    pub fn status_questionable_enable_get(&mut self) -> crate::Result<QuestionableReg> {
+        self.idempotent(|fc| fc.status_reg_get("STAT:QUES:ENAB?"))
+        .map(QuestionableReg::from_bits_truncate)
+    }
+
+    /// `STATus:QUEStionable:ENABle <bits>`
+    pub fn status_questionable_enable_set(&mut self, qreg: QuestionableReg) -> crate::Result<()> {
+        self.command(&format!("STAT:QUES:ENAB {}", qreg.bits()))
+    }
+
+    /// `STATus:PRESet`
+    pub fn status_preset(&mut self) -> crate::Result<()> {
+        self.command("STAT:PRES")
+    }
+}
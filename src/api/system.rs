@@ -1,31 +1,90 @@
 use crate::{KsFc};
-use crate::format::{into_text, ParseError};
+use crate::format::{parse_string, parse_bool, ParseError};
 use crate::{KsDevErr};
 use crate::constants::*;
 
 
+/// Parses `SYSTem:ERRor?` reply into error code and message.
+fn parse_system_error(text: &str) -> Result<(i32, String), ParseError> {
+    let mut parts = text.trim().splitn(2, ',');
+    let code = parts.next().ok_or(ParseError::EndOfString)
+    .and_then(|part| parse!(part, i32).map(|v| v.0))?;
+    let message = match parts.next() {
+        Some(part) => parse_string(part)?,
+        None => String::new(),
+    };
+    Ok((code, message))
+}
+
+/// Parses `SYSTem:ERRor?` reply into device error, if any.
+pub(crate) fn parse_error_reply(text: &str) -> crate::Result<Option<KsDevErr>> {
+    parse_system_error(text)
+    .map(|(code, message)| {
+        if code == NO_ERROR_CODE {
+            None
+        } else {
+            Some(KsDevErr::with_message(code, &message))
+        }
+    })
+    .map_err(|e| e.into())
+}
+
 impl KsFc {
     /// `SYSTem:ERRor?`
     pub fn system_error(&mut self) -> crate::Result<Option<KsDevErr>> {
-        self.send(b"SYST:ERR?")
-        .and_then(|()| self.receive())
-        .and_then(|data| into_text(data))
-        .and_then(|text| {
-            text.splitn(2, ',').next().ok_or(ParseError::EndOfString)
-            .and_then(|part| {
-                parse!(part, i32).map(|v| v.0).map_err(|e| e.into())
-            }).map_err(|e| e.into())
-        })
-        .and_then(|code| {
-            if code == NO_ERROR_CODE {
-                Ok(None)
-            } else {
-                match KsDevErr::new(code) {
-                    Some(e) => Ok(Some(e)),
-                    None => Err(format!("Unknown error code: '{}'", code).into()),
-                }
-            }
-        })
+        self.query("SYST:ERR?", parse_error_reply)
+    }
+
+    /// Reads `SYSTem:ERRor?` until the error queue is empty.
+    pub fn system_errors(&mut self) -> crate::Result<Vec<KsDevErr>> {
+        let mut errors = Vec::new();
+        while let Some(e) = self.system_error()? {
+            errors.push(e);
+        }
+        Ok(errors)
+    }
+
+    /// `SYSTem:LOCK:REQuest?`, whether the lock was granted to this session
+    ///
+    /// Requests are counted, each granted one needs its own release.
+    pub fn system_lock_request(&mut self) -> crate::Result<bool> {
+        self.query("SYST:LOCK:REQ?", |text| parse_bool(text).map_err(|e| e.into()))
+    }
+
+    /// `SYSTem:LOCK:RELease`
+    pub fn system_lock_release(&mut self) -> crate::Result<()> {
+        self.command("SYST:LOCK:REL")
+    }
+
+    /// `SYSTem:LOCK:OWNer?`, interface holding the lock or `NONE`
+    pub fn system_lock_owner(&mut self) -> crate::Result<String> {
+        self.idempotent(|fc| fc.query("SYST:LOCK:OWN?", |text| parse_string(text).map_err(|e| e.into())))
+    }
+
+    /// `SYSTem:LOCK:NAME?`, interface of this session as reported by `SYST:LOCK:OWN?`
+    pub fn system_lock_name(&mut self) -> crate::Result<String> {
+        self.idempotent(|fc| fc.query("SYST:LOCK:NAME?", |text| parse_string(text).map_err(|e| e.into())))
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_no_error() {
+        assert_eq!(
+            parse_system_error("+0,\"No error\"\n").unwrap(),
+            (0, String::from("No error")),
+        );
+    }
+
+    #[test]
+    fn parse_error_message() {
+        assert_eq!(
+            parse_system_error("-221,\"Settings conflict; trigger source is BUS\"").unwrap(),
+            (-221, String::from("Settings conflict; trigger source is BUS")),
+        );
+    }
+}
@@ -1,64 +1,64 @@
 use std::time::Duration;
 
 use crate::{
-    KsFc,
-    types::{TriggerSource},
-    format::{parse, into_text, dur_as_secs, secs_as_dur},
+    KsFc, KsDevErr,
+    deverr::{KsDevErrVariant, ConflictKind},
+    types::{TriggerSource, Slope},
+    format::{parse, dur_as_secs, secs_as_dur},
 };
 
 
 impl KsFc {
     /// `TRIGger:COUNt <count>`
     pub fn trigger_count_set(&mut self, count: usize) -> crate::Result<()> {
-        self.send(format!("TRIG:COUN {}", count).as_bytes())
-        .and_then(|()| self.system_error())
-        .and_then(|e| match e {
-            Some(e) => Err(e.into()),
-            None => Ok(()),
-        })
+        self.command(&format!("TRIG:COUN {}", count))
     }
     /// `TRIGger:COUNt?`
     pub fn trigger_count_get(&mut self) -> crate::Result<usize> {
-        self.send(b"TRIG:COUN?")
-        .and_then(|()| self.receive())
-        .and_then(|data| into_text(data))
-        .and_then(|text| parse::<usize>(&text).map_err(|e| e.into()))
+        self.idempotent(|fc| fc.query("TRIG:COUN?", |text| parse::<usize>(text).map_err(|e| e.into())))
     }
 
     /// `TRIGger:DELay <time>`
     pub fn trigger_delay_set(&mut self, time: Duration) -> crate::Result<()> {
-        self.send(format!("TRIG:DEL {}", dur_as_secs(time)).as_bytes())
-        .and_then(|()| self.system_error())
-        .and_then(|e| match e {
-            Some(e) => Err(e.into()),
-            None => Ok(()),
-        })
+        self.command(&format!("TRIG:DEL {}", dur_as_secs(time)))
     }
     /// `TRIGger:DELay?`
     pub fn trigger_delay_get(&mut self) -> crate::Result<Duration> {
-        self.send(b"TRIG:DEL?")
-        .and_then(|()| self.receive())
-        .and_then(|data| into_text(data))
-        .and_then(|text| {
-            parse::<f64>(&text)
+        self.idempotent(|fc| fc.query("TRIG:DEL?", |text| {
+            parse::<f64>(text)
             .map(|s| secs_as_dur(s).unwrap())
             .map_err(|e| e.into())
-        })
+        }))
     }
 
     /// `TRIGger:SOURce <source>`
     pub fn trigger_source_set(&mut self, source: TriggerSource) -> crate::Result<()> {
-        let text = format!("TRIG:SOUR {}", match source {
-            TriggerSource::Immediate => "IMM",
-            TriggerSource::External => "EXT",
-            TriggerSource::Bus => "BUS",
-        });
-        self.send(text.as_bytes())
-        .and_then(|()| self.system_error())
-        .and_then(|e| match e {
-            Some(e) => Err(e.into()),
-            None => Ok(()),
-        })
+        self.command(&format!("TRIG:SOUR {}", source.mnemonic()))
+    }
+    /// `TRIGger:SOURce?`
+    pub fn trigger_source_get(&mut self) -> crate::Result<TriggerSource> {
+        self.idempotent(|fc| fc.query("TRIG:SOUR?", |text| text.parse::<TriggerSource>().map_err(|e| e.into())))
     }
-}
 
+    /// `TRIGger:SLOPe <slope>`, edge of external trigger signal
+    pub fn trigger_slope_set(&mut self, slope: Slope) -> crate::Result<()> {
+        self.command(&format!("TRIG:SLOP {}", slope.mnemonic()))
+    }
+    /// `TRIGger:SLOPe?`
+    pub fn trigger_slope_get(&mut self) -> crate::Result<Slope> {
+        self.idempotent(|fc| fc.query("TRIG:SLOP?", |text| text.parse::<Slope>().map_err(|e| e.into())))
+    }
+
+    /// `*TRG`, triggers the device waiting for `TriggerSource::Bus`.
+    ///
+    /// Trigger source is checked first, the device would silently ignore
+    /// the trigger otherwise.
+    pub fn software_trigger(&mut self) -> crate::Result<()> {
+        match self.trigger_source_get()? {
+            TriggerSource::Bus => self.command("*TRG"),
+            _ => Err(Self::rejected("*TRG", crate::Error::Dev(KsDevErr::from(
+                KsDevErrVariant::SettingsConflict(ConflictKind::TriggerNotBus)
+            )))),
+        }
+    }
+}
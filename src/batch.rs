@@ -0,0 +1,294 @@
+use std::marker::{PhantomData};
+use std::str::{FromStr};
+use std::time::{Duration, Instant};
+use std::error::{Error};
+
+use crate::{KsFc, ErrorCheck, Context};
+use crate::types::{ChannelNo, TriggerSource, Slope};
+use crate::format::{parse, parse_secs, into_text, split_unquoted, dur_as_secs};
+
+
+/// Position of query result in `BatchReply`.
+pub struct Slot<T> {
+    index: usize,
+    parse: fn(&str) -> crate::Result<T>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> Clone for Slot<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for Slot<T> {}
+
+fn parse_value<T: FromStr>(text: &str) -> crate::Result<T>
+where T::Err: 'static + Error + Send + Sync {
+    parse::<T>(text).map_err(|e| e.into())
+}
+
+/// Replies to batch queries.
+#[derive(Debug, Clone)]
+pub struct BatchReply {
+    values: Vec<String>,
+}
+
+impl BatchReply {
+    pub fn get<T>(&self, slot: Slot<T>) -> crate::Result<T> {
+        let text = self.values.get(slot.index).ok_or("No such query in batch")?;
+        (slot.parse)(text)
+    }
+}
+
+/// Commands and queries sent to device as a single `;`-joined message.
+///
+/// Queries with binary block replies (`R?`) cannot be batched.
+/// Batch is never repeated by retry policy.
+pub struct Batch<'a> {
+    fc: &'a mut KsFc,
+    items: Vec<String>,
+    queries: usize,
+}
+
+impl<'a> Batch<'a> {
+    pub(crate) fn new(fc: &'a mut KsFc) -> Self {
+        Batch { fc, items: Vec::new(), queries: 0 }
+    }
+
+    /// Appends command that has no reply.
+    pub fn command(&mut self, cmd: &str) -> &mut Self {
+        self.items.push(String::from(cmd));
+        self
+    }
+
+    /// Appends query which reply is parsed with `FromStr`.
+    pub fn query<T: FromStr>(&mut self, cmd: &str) -> Slot<T>
+    where T::Err: 'static + Error + Send + Sync {
+        self.query_with(cmd, parse_value::<T>)
+    }
+
+    /// Appends query which reply is parsed with `parse`.
+    pub fn query_with<T>(&mut self, cmd: &str, parse: fn(&str) -> crate::Result<T>) -> Slot<T> {
+        self.items.push(String::from(cmd));
+        self.queries += 1;
+        Slot { index: self.queries - 1, parse, _phantom: PhantomData }
+    }
+
+    /// Sends the batch and receives query replies.
+    ///
+    /// Errors found in device queue after the batch are reported for the whole batch.
+    /// The error check (or `*OPC?` if errors are not checked) is sent as a separate
+    /// message, so its reply arrives even if device drops the rest of the batch
+    /// after a failed command. Without error check a dropped batch with single
+    /// query can't be told from `*OPC?` reply and fails with timeout. With error
+    /// check `SYST:ERR?` itself should not be queried in a batch, its reply is
+    /// taken for the final one.
+    pub fn send(self) -> crate::Result<BatchReply> {
+        let Batch { fc, items, queries } = self;
+        if items.is_empty() {
+            return Ok(BatchReply { values: Vec::new() });
+        }
+        let check = match fc.check {
+            ErrorCheck::Never => false,
+            ErrorCheck::AfterEach | ErrorCheck::Batched => true,
+        };
+        let cmd = join_commands(&items);
+
+        let start = Instant::now();
+        let context = |response: Option<&str>| Context {
+            command: cmd.clone(),
+            response: response.map(|r| r.as_bytes().to_vec()),
+            elapsed: start.elapsed(),
+            errors: Vec::new(),
+        };
+        fc.send(cmd.as_bytes()).map_err(|e| e.with_context(context(None)))?;
+        if queries == 0 && !check {
+            return Ok(BatchReply { values: Vec::new() });
+        }
+        fc.send(if check { b"SYST:ERR?" } else { b"*OPC?" })
+        .map_err(|e| e.with_context(context(None)))?;
+
+        let mut receive = || fc.receive().and_then(into_text).map_err(|e| e.with_context(context(None)));
+        let first = if queries > 0 {
+            let text = receive()?;
+            Some((split_reply(&text, queries, check), text))
+        } else {
+            None
+        };
+        let last = match &first {
+            Some((BatchText::Final, text)) => text.clone(),
+            _ => receive()?,
+        };
+        let response = first.as_ref().map_or(&last, |(_, text)| text);
+        if check {
+            crate::api::system::parse_error_reply(&last)
+            .and_then(|e| fc.check_error(e, context(Some(response))))
+            .map_err(|e| e.with_context(context(Some(response))))?;
+        }
+        match first {
+            None => Ok(BatchReply { values: Vec::new() }),
+            Some((BatchText::Values(values), _)) => Ok(BatchReply { values }),
+            Some((BatchText::Partial(n), _)) => Err(crate::Error::from(format!(
+                "Batch reply has {} values, {} expected", n, queries,
+            )).with_context(context(Some(response)))),
+            Some((BatchText::Final, _)) => Err(crate::Error::from(
+                "Batch was not executed"
+            ).with_context(context(Some(response)))),
+        }
+    }
+}
+
+/// Joins commands into single message.
+///
+/// Common commands (`*CLS`) can't follow `:`, so they are separated with `;` only.
+fn join_commands(items: &[String]) -> String {
+    let mut msg = String::new();
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            msg.push_str(if item.starts_with('*') { ";" } else { ";:" });
+        }
+        msg.push_str(item);
+    }
+    msg
+}
+
+/// First reply to the batch.
+#[derive(Debug, PartialEq)]
+enum BatchText {
+    /// All query replies
+    Values(Vec<String>),
+    /// Number of replies if some of them are missing
+    Partial(usize),
+    /// Reply to the final check, batch gave no output
+    Final,
+}
+
+fn split_reply(text: &str, queries: usize, check: bool) -> BatchText {
+    let values: Vec<String> = split_unquoted(text.trim(), ';')
+    .into_iter().map(String::from).collect();
+    if check && is_error_reply(text) {
+        // Dropped batch leaves only the error which made device drop it,
+        // even if it looks like the reply to a single query.
+        BatchText::Final
+    } else if values.len() == queries {
+        BatchText::Values(values)
+    } else if is_final_reply(text, check) {
+        BatchText::Final
+    } else {
+        BatchText::Partial(values.len())
+    }
+}
+
+/// Whether `text` is the reply to `SYST:ERR?` or `*OPC?` sent after the batch.
+fn is_final_reply(text: &str, check: bool) -> bool {
+    let text = text.trim();
+    if check {
+        text.contains(',') && crate::api::system::parse_error_reply(text).is_ok()
+    } else {
+        text == "1" || text == "+1"
+    }
+}
+
+/// Whether `text` is `SYST:ERR?` reply reporting an error.
+fn is_error_reply(text: &str) -> bool {
+    let text = text.trim();
+    text.contains(',') && matches!(crate::api::system::parse_error_reply(text), Ok(Some(_)))
+}
+
+// Typed batch commands
+impl<'a> Batch<'a> {
+    /// `ABORt`
+    pub fn abort(&mut self) -> &mut Self {
+        self.command("ABOR")
+    }
+
+    /// `INITiate`
+    pub fn initiate(&mut self) -> &mut Self {
+        self.command("INIT")
+    }
+
+    /// `CONFigure:FREQuency @<channel>`
+    pub fn configure_frequency(&mut self, cn: ChannelNo) -> &mut Self {
+        self.command(&format!("CONF:FREQ (@{})", cn as u8))
+    }
+
+    /// `SENSe:FREQuency:GATE:TIME <time>`
+    pub fn sense_frequency_gate_time_set(&mut self, time: Duration) -> &mut Self {
+        self.command(&format!("FREQ:GATE:TIME {}", dur_as_secs(time)))
+    }
+    /// `SENSe:FREQuency:GATE:TIME?`
+    pub fn sense_frequency_gate_time_get(&mut self) -> Slot<Duration> {
+        self.query_with("FREQ:GATE:TIME?", parse_secs)
+    }
+
+    /// `TRIGger:COUNt <count>`
+    pub fn trigger_count_set(&mut self, count: usize) -> &mut Self {
+        self.command(&format!("TRIG:COUN {}", count))
+    }
+    /// `TRIGger:COUNt?`
+    pub fn trigger_count_get(&mut self) -> Slot<usize> {
+        self.query("TRIG:COUN?")
+    }
+
+    /// `TRIGger:DELay <time>`
+    pub fn trigger_delay_set(&mut self, time: Duration) -> &mut Self {
+        self.command(&format!("TRIG:DEL {}", dur_as_secs(time)))
+    }
+    /// `TRIGger:DELay?`
+    pub fn trigger_delay_get(&mut self) -> Slot<Duration> {
+        self.query_with("TRIG:DEL?", parse_secs)
+    }
+
+    /// `TRIGger:SOURce <source>`
+    pub fn trigger_source_set(&mut self, source: TriggerSource) -> &mut Self {
+        self.command(&format!("TRIG:SOUR {}", source.mnemonic()))
+    }
+    /// `TRIGger:SOURce?`
+    pub fn trigger_source_get(&mut self) -> Slot<TriggerSource> {
+        self.query("TRIG:SOUR?")
+    }
+
+    /// `TRIGger:SLOPe <slope>`
+    pub fn trigger_slope_set(&mut self, slope: Slope) -> &mut Self {
+        self.command(&format!("TRIG:SLOP {}", slope.mnemonic()))
+    }
+    /// `TRIGger:SLOPe?`
+    pub fn trigger_slope_get(&mut self) -> Slot<Slope> {
+        self.query("TRIG:SLOP?")
+    }
+}
+
+impl KsFc {
+    /// Starts collecting commands to send them in one message.
+    pub fn batch(&mut self) -> Batch<'_> {
+        Batch::new(self)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn join() {
+        let items: Vec<String> = ["*CLS", "TRIG:COUN 2", "*ESE 1", "TRIG:COUN?"]
+        .iter().map(|s| String::from(*s)).collect();
+        assert_eq!(join_commands(&items), "*CLS;:TRIG:COUN 2;*ESE 1;:TRIG:COUN?");
+    }
+
+    #[test]
+    fn reply() {
+        assert_eq!(
+            split_reply("+2;+1.0E-02\n", 2, true),
+            BatchText::Values(vec![String::from("+2"), String::from("+1.0E-02")]),
+        );
+        // Device dropped the rest of the batch after a command error
+        assert_eq!(split_reply("-113,\"Undefined header\"\n", 2, true), BatchText::Final);
+        assert_eq!(split_reply("-113,\"Undefined header\"\n", 1, true), BatchText::Final);
+        assert_eq!(split_reply("+0,\"No error\"\n", 2, true), BatchText::Final);
+        assert_eq!(split_reply("+2\n", 1, true), BatchText::Values(vec![String::from("+2")]));
+        assert_eq!(split_reply("+1\n", 2, false), BatchText::Final);
+        assert_eq!(split_reply("+2\n", 2, true), BatchText::Partial(1));
+    }
+}
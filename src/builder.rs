@@ -0,0 +1,116 @@
+use std::time::{Duration};
+
+use crate::{KsFc, RetryPolicy, ErrorCheck};
+use crate::constants::*;
+
+
+/// Connection transport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Transport {
+    /// Raw SCPI socket
+    #[default]
+    Socket,
+}
+
+impl Transport {
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Transport::Socket => SOCKET_PORT,
+        }
+    }
+}
+
+/// `KsFc` constructor with connection options.
+#[derive(Debug, Clone)]
+pub struct KsFcBuilder {
+    host: String,
+    port: Option<u16>,
+    transport: Transport,
+    connect_timeout: Duration,
+    read_timeout: Option<Duration>,
+    auto_connect: bool,
+    reset: bool,
+    clear: bool,
+    error_check: ErrorCheck,
+    retry: Option<RetryPolicy>,
+}
+
+impl KsFcBuilder {
+    pub fn new(host: &str) -> Self {
+        Self {
+            host: String::from(host),
+            port: None,
+            transport: Transport::default(),
+            connect_timeout: CONNECT_TIMEOUT,
+            read_timeout: None,
+            auto_connect: true,
+            reset: false,
+            clear: false,
+            error_check: ErrorCheck::default(),
+            retry: None,
+        }
+    }
+
+    /// Port, transport default port is used if not set.
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+    pub fn transport(mut self, transport: Transport) -> Self {
+        self.transport = transport;
+        self
+    }
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+    /// Timeout of waiting for replies, connect timeout is used if not set.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+    /// Connect to device in `build`, enabled by default.
+    pub fn auto_connect(mut self, enable: bool) -> Self {
+        self.auto_connect = enable;
+        self
+    }
+    /// Send `*RST` after connection in `build`.
+    pub fn reset(mut self, enable: bool) -> Self {
+        self.reset = enable;
+        self
+    }
+    /// Send `*CLS` after connection in `build`.
+    pub fn clear(mut self, enable: bool) -> Self {
+        self.clear = enable;
+        self
+    }
+    pub fn error_check(mut self, check: ErrorCheck) -> Self {
+        self.error_check = check;
+        self
+    }
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Creates device handle and connects to it if `auto_connect` is set.
+    ///
+    /// `reset` and `clear` take effect only with `auto_connect`.
+    pub fn build(self) -> crate::Result<KsFc> {
+        let port = self.port.unwrap_or(self.transport.default_port());
+        let mut fc = KsFc::new(&self.host, Some(port), self.connect_timeout);
+        fc.read_timeout = self.read_timeout;
+        fc.check = self.error_check;
+        fc.retry = self.retry;
+        if self.auto_connect {
+            fc.connect()?;
+            if self.reset {
+                fc.rst()?;
+            }
+            if self.clear {
+                fc.cls()?;
+            }
+        }
+        Ok(fc)
+    }
+}
@@ -0,0 +1,11 @@
+/// Policy of checking device error queue after commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorCheck {
+    /// Errors are left in the queue, use `system_errors` to read them
+    Never,
+    /// `SYSTem:ERRor?` is queried after each command
+    #[default]
+    AfterEach,
+    /// `SYSTem:ERRor?` is sent right after each command without waiting, so it costs one round-trip
+    Batched,
+}
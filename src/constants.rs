@@ -1,6 +1,24 @@
 use std::time::Duration;
 
 
+pub static SOCKET_PORT: u16 = 5025;
+pub static CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
 pub static CAL_TIMEOUT: Duration = Duration::from_secs(20);
+pub static STB_POLL_PERIOD: Duration = Duration::from_millis(50);
 pub static ERROR_VALUE: f64 = 9.91e37;
 pub static NO_ERROR_CODE: i32 = 0;
+/// Reading memory size, limits trigger count × sample count
+pub static MAX_READINGS: usize = 1_000_000;
+pub static FILE_TIMEOUT: Duration = Duration::from_secs(30);
+pub static SCREENSHOT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest `DISP:TEXT` message
+pub static DISPLAY_TEXT_MAX_LEN: usize = 40;
+/// Range of `DISP:DIG:MASK` digits
+pub static DISPLAY_DIGITS_MIN: u8 = 3;
+pub static DISPLAY_DIGITS_MAX: u8 = 15;
+pub static LOCK_POLL_PERIOD: Duration = Duration::from_millis(100);
+pub static GPIB_ADDR_MAX: u8 = 30;
+/// Longest `SYST:COMM:LAN:HOST` name
+pub static HOSTNAME_MAX_LEN: usize = 15;
+/// Period of connection attempts while LAN interface restarts
+pub static RESTART_POLL_PERIOD: Duration = Duration::from_secs(1);
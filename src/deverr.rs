@@ -2,288 +2,571 @@ use std::error;
 use std::fmt;
 use std::collections::HashMap;
 
+use self::KsDevErrVariant::*;
 
-static DEV_ERR_LIST: [(i32, &'static str); 225] = [
+
+static DEV_ERR_LIST: [(i32, &str, Option<KsDevErrVariant>); 225] = [
     // Command Errors
-    (-100, "Command error"),
-    (-101, "Invalid character"),
-    (-102, "Syntax error"),
-    (-103, "Invalid separator"),
-    (-104, "Data type error"),
-    (-108, "Parameter not allowed"),
-    (-109, "Missing parameter"),
-    (-110, "Command header error"),
-    (-111, "Header separator error"),
-    (-112, "Program mnemonic too long"),
-    (-113, "Undefined header"),
-    (-114, "Header suffix out of range"),
-    (-120, "Numeric data error"),
-    (-121, "Invalid character in number"),
-    (-123, "Exponent too large"),
-    (-123, "Numeric overflow"),
-    (-124, "Too many digits"),
-    (-128, "Numeric data not allowed"),
-    (-130, "Suffix error"),
-    (-131, "Invalid suffix"),
-    (-134, "Suffix too long"),
-    (-138, "Suffix not allowed"),
-    (-140, "Character data error"),
-    (-141, "Invalid character data"),
-    (-144, "Character data too long"),
-    (-148, "Character data not allowed"),
-    (-150, "String data error"),
-    (-151, "Invalid string data"),
-    (-158, "String data not allowed"),
-    (-160, "Block data error"),
-    (-161, "Invalid block data"),
-    (-168, "Block data not allowed"),
-    (-170, "Expression error"),
-    (-171, "Invalid expression"),
-    (-178, "Expression data not allowed"),
+    (-100, "Command error", None),
+    (-101, "Invalid character", None),
+    (-102, "Syntax error", None),
+    (-103, "Invalid separator", None),
+    (-104, "Data type error", None),
+    (-108, "Parameter not allowed", None),
+    (-109, "Missing parameter", None),
+    (-110, "Command header error", None),
+    (-111, "Header separator error", None),
+    (-112, "Program mnemonic too long", None),
+    (-113, "Undefined header", None),
+    (-114, "Header suffix out of range", None),
+    (-120, "Numeric data error", None),
+    (-121, "Invalid character in number", None),
+    (-123, "Exponent too large", Some(ExponentTooLarge)),
+    (-123, "Numeric overflow", Some(NumericOverflow)),
+    (-124, "Too many digits", None),
+    (-128, "Numeric data not allowed", None),
+    (-130, "Suffix error", None),
+    (-131, "Invalid suffix", None),
+    (-134, "Suffix too long", None),
+    (-138, "Suffix not allowed", None),
+    (-140, "Character data error", None),
+    (-141, "Invalid character data", None),
+    (-144, "Character data too long", Some(CharacterDataTooLong)),
+    (-148, "Character data not allowed", None),
+    (-150, "String data error", None),
+    (-151, "Invalid string data", Some(InvalidString)),
+    (-158, "String data not allowed", None),
+    (-160, "Block data error", None),
+    (-161, "Invalid block data", None),
+    (-168, "Block data not allowed", None),
+    (-170, "Expression error", None),
+    (-171, "Invalid expression", None),
+    (-178, "Expression data not allowed", None),
 
     // Execution Errors
-    (-203, "Command protected; pulsed measurements require option 150"),
-    (-213, "INIT ignored"),
-    (-221, "Settings conflict"),
-    (-221, "Settings conflict; *TRG when TRIG:SOUR BUS not selected; trigger ignored"),
-    (-221, "Settings conflict; CALC:SCAL:REF 0.0 not compatible with CALC:SCAL:FUNC PCT|PPM|PPB; CALC:SCAL:FUNC set to NULL"),
-    (-221, "Settings conflict; CALC:STATe or CALC:AVER:STATe OFF set"),
-    (-221, "Settings conflict; CALC2:TRAN:HIST:STATe OFF set"),
-    (-221, "Settings conflict; GATE:STAR:DEL:SOUR EVENts on channel 3; GATE:STAR:DEL:SOUR IMM set"),
-    (-221, "Settings conflict; GATE:STOP:HOLD:SOUR EVENts on channel 3; GATE:STOP:HOLD:SOUR TIM set"),
-    (-221, "Settings conflict; ROSC:EXT:CHEC can only be sent when ROSC:SOUR EXT selectd;AUTO OFF set"),
-    (-221, "Settings conflict; SAMP:COUN limited to 100,000 in time stamp function with SENS:TST:RATE SLOW; SAMP:COUN set to 100,000"),
-    (-221, "Settings conflict; SENS:FREQ:GATE:SOUR on measurement channel; SENS:FREQ:GATE:SOUR set to EXT"),
-    (-221, "Settings conflict; SENS:FREQ:MODE CONT only valid for frequency/period; SENS:FREQ:MODE AUTO set"),
-    (-221, "Settings conflict; SENS:GATE:EXT:SOUR on measurement channel; SENS:GATE:EXT:SOUR set to EXT"),
-    (-221, "Settings conflict; SENS:TINT:GATE:SOUR on measurement channel; SENS:TINT:GATE:SOUR set to EXT"),
-    (-221, "Settings conflict; SENS:TOT:GATE:SOUR on measurement channel; SENS:TOT:GATE:SOUR set to EXT"),
-    (-221, "Settings conflict; cannot auto-level input channel used as gate; INP:LEV set to 0V, auto-level off"),
-    (-221, "Settings conflict; cannot delete state selected and enabled for automatic power-on recall"),
-    (-221, "Settings conflict; cannot gate time interval-type measurement with baseband channel; SENS:GATE:EXT:SOUR set to BNC"),
-    (-221, "Settings conflict; cannot have immediate, no-holdoff gate stop for frequency or totalize meas- urements; GATE:STOP:HOLD:SOUR TIME set"),
-    (-221, "Settings conflict; cannot use READ? with continuous totalize"),
-    (-221, "Settings conflict; external gating not compatible with gate output; gate output disabled"),
-    (-221, "Settings conflict; histogram bin width is 0.0; CALC2:TRAN:HIST:RANG:AUTO ON set"),
-    (-221, "Settings conflict; histogram lower range > upper range; CALC2:TRAN:HIST:RANG:AUTO ON set"),
-    (-221, "Settings conflict; infinite stop holdoff time for frequency; SENS:FREQ:GATE:SOUR set to TIME"),
-    (-221, "Settings conflict; infinite stop holdoff time for time interval; SENS:TINT:GATE:SOUR set to IMM"),
-    (-221, "Settings conflict; input range not compatible with input probe factor; INP:RANG set to 50V range"),
-    (-221, "Settings conflict; input threshold voltage > input range; threshold clipped to range"),
-    (-221, "Settings conflict; low reference >= high reference"),
-    (-221, "Settings conflict; low reference >= high reference; reference values changed to defaults"),
-    (-221, "Settings conflict; lower limit > upper limit; CALC:LIM:UPP set to CALC:LIM:LOW value"),
-    (-221, "Settings conflict; lower reference and upper reference have different units"),
-    (-221, "Settings conflict; stop holdoff < minimum gate time for frequency or totalize; SENSe:GATE:STOP:HOLD:TIME set to minimum"),
-    (-221, "Settings conflict; trigger source is BUS"),
-    (-222, "Data out of range"),
-    (-222, "Data out of range; value clipped to lower limit"),
-    (-222, "Data out of range; value clipped to upper limit"),
-    (-223, "Too much data"),
-    (-224, "Illegal parameter value"),
-    (-225, "Out of memory; measurement data overrun"),
-    (-230, "Data corrupt or stale"),
-    (-240, "Hardware error; CPU board initialization failed"),
-    (-240, "Hardware error; GPIB interface failed"),
-    (-240, "Hardware error; cannot communicate with channel 3 hardware"),
-    (-240, "Hardware error; cannot communicate with measurement hardware"),
-    (-240, "Hardware error; channel 3 operation failed"),
-    (-240, "Hardware error; measurement hardware initialization failed"),
-    (-240, "Hardware error; measurement operation failed"),
-    (-240, "Hardware error; failed to program measurement FPGA security EEProm"),
-    (-241, "Hardware missing"),
-    (-250, "Mass storage error: file read/write error"),
-    (-252, "Missing media"),
-    (-254, "Media full"),
-    (-256, "File or folder name not found"),
-    (-257, "File name error; invalid character in name"),
-    (-257, "File name error; relative path not allowed"),
-    (-257, "File name error; path too long"),
-    (-257, "File name error; path is a folder name"),
-    (-257, "File name error; not a folder name"),
-    (-257, "File name error; drive name missing or not recognized"),
-    (-257, "File name error; path name missing"),
-    (-257, "File name error; file or folder already exists"),
-    (-257, "File name error; folder not empty"),
-    (-257, "File name error; folder is default folder"),
-    (-257, "File name error; access denied"),
-    (-257, "File name error"),
-    (-257, "File name error; file too large"),
-    (-257, "File name error; unknown file extension"),
+    (-203, "Command protected; pulsed measurements require option 150", None),
+    (-213, "INIT ignored", None),
+    (-221, "Settings conflict", Some(SettingsConflict(ConflictKind::Generic))),
+    (-221, "Settings conflict; *TRG when TRIG:SOUR BUS not selected; trigger ignored", Some(SettingsConflict(ConflictKind::TriggerNotBus))),
+    (-221, "Settings conflict; CALC:SCAL:REF 0.0 not compatible with CALC:SCAL:FUNC PCT|PPM|PPB; CALC:SCAL:FUNC set to NULL", Some(SettingsConflict(ConflictKind::ScaleZeroReference))),
+    (-221, "Settings conflict; CALC:STATe or CALC:AVER:STATe OFF set", Some(SettingsConflict(ConflictKind::CalcDisabled))),
+    (-221, "Settings conflict; CALC2:TRAN:HIST:STATe OFF set", Some(SettingsConflict(ConflictKind::HistogramDisabled))),
+    (-221, "Settings conflict; GATE:STAR:DEL:SOUR EVENts on channel 3; GATE:STAR:DEL:SOUR IMM set", Some(SettingsConflict(ConflictKind::StartDelayEventsCh3))),
+    (-221, "Settings conflict; GATE:STOP:HOLD:SOUR EVENts on channel 3; GATE:STOP:HOLD:SOUR TIM set", Some(SettingsConflict(ConflictKind::StopHoldoffEventsCh3))),
+    (-221, "Settings conflict; ROSC:EXT:CHEC can only be sent when ROSC:SOUR EXT selectd;AUTO OFF set", Some(SettingsConflict(ConflictKind::RoscCheckNotExternal))),
+    (-221, "Settings conflict; SAMP:COUN limited to 100,000 in time stamp function with SENS:TST:RATE SLOW; SAMP:COUN set to 100,000", Some(SettingsConflict(ConflictKind::TimestampSampleCount))),
+    (-221, "Settings conflict; SENS:FREQ:GATE:SOUR on measurement channel; SENS:FREQ:GATE:SOUR set to EXT", Some(SettingsConflict(ConflictKind::FreqGateOnMeasChannel))),
+    (-221, "Settings conflict; SENS:FREQ:MODE CONT only valid for frequency/period; SENS:FREQ:MODE AUTO set", Some(SettingsConflict(ConflictKind::ContinuousModeFunction))),
+    (-221, "Settings conflict; SENS:GATE:EXT:SOUR on measurement channel; SENS:GATE:EXT:SOUR set to EXT", Some(SettingsConflict(ConflictKind::ExtGateOnMeasChannel))),
+    (-221, "Settings conflict; SENS:TINT:GATE:SOUR on measurement channel; SENS:TINT:GATE:SOUR set to EXT", Some(SettingsConflict(ConflictKind::TintGateOnMeasChannel))),
+    (-221, "Settings conflict; SENS:TOT:GATE:SOUR on measurement channel; SENS:TOT:GATE:SOUR set to EXT", Some(SettingsConflict(ConflictKind::TotGateOnMeasChannel))),
+    (-221, "Settings conflict; cannot auto-level input channel used as gate; INP:LEV set to 0V, auto-level off", Some(SettingsConflict(ConflictKind::AutoLevelGateChannel))),
+    (-221, "Settings conflict; cannot delete state selected and enabled for automatic power-on recall", Some(SettingsConflict(ConflictKind::DeleteAutoRecallState))),
+    (-221, "Settings conflict; cannot gate time interval-type measurement with baseband channel; SENS:GATE:EXT:SOUR set to BNC", Some(SettingsConflict(ConflictKind::TintBasebandGate))),
+    (-221, "Settings conflict; cannot have immediate, no-holdoff gate stop for frequency or totalize meas- urements; GATE:STOP:HOLD:SOUR TIME set", Some(SettingsConflict(ConflictKind::ImmediateGateStop))),
+    (-221, "Settings conflict; cannot use READ? with continuous totalize", Some(SettingsConflict(ConflictKind::ContinuousTotalizeRead))),
+    (-221, "Settings conflict; external gating not compatible with gate output; gate output disabled", Some(SettingsConflict(ConflictKind::ExtGateOutput))),
+    (-221, "Settings conflict; histogram bin width is 0.0; CALC2:TRAN:HIST:RANG:AUTO ON set", Some(SettingsConflict(ConflictKind::HistogramZeroWidth))),
+    (-221, "Settings conflict; histogram lower range > upper range; CALC2:TRAN:HIST:RANG:AUTO ON set", Some(SettingsConflict(ConflictKind::HistogramRange))),
+    (-221, "Settings conflict; infinite stop holdoff time for frequency; SENS:FREQ:GATE:SOUR set to TIME", Some(SettingsConflict(ConflictKind::FreqInfiniteHoldoff))),
+    (-221, "Settings conflict; infinite stop holdoff time for time interval; SENS:TINT:GATE:SOUR set to IMM", Some(SettingsConflict(ConflictKind::TintInfiniteHoldoff))),
+    (-221, "Settings conflict; input range not compatible with input probe factor; INP:RANG set to 50V range", Some(SettingsConflict(ConflictKind::RangeProbe))),
+    (-221, "Settings conflict; input threshold voltage > input range; threshold clipped to range", Some(SettingsConflict(ConflictKind::ThresholdRange))),
+    (-221, "Settings conflict; low reference >= high reference", Some(SettingsConflict(ConflictKind::ReferenceOrder))),
+    (-221, "Settings conflict; low reference >= high reference; reference values changed to defaults", Some(SettingsConflict(ConflictKind::ReferenceOrderReset))),
+    (-221, "Settings conflict; lower limit > upper limit; CALC:LIM:UPP set to CALC:LIM:LOW value", Some(SettingsConflict(ConflictKind::LimitOrder))),
+    (-221, "Settings conflict; lower reference and upper reference have different units", Some(SettingsConflict(ConflictKind::ReferenceUnits))),
+    (-221, "Settings conflict; stop holdoff < minimum gate time for frequency or totalize; SENSe:GATE:STOP:HOLD:TIME set to minimum", Some(SettingsConflict(ConflictKind::StopHoldoffMin))),
+    (-221, "Settings conflict; trigger source is BUS", Some(SettingsConflict(ConflictKind::TriggerSourceBus))),
+    (-222, "Data out of range", Some(DataOutOfRange(RangeKind::Generic))),
+    (-222, "Data out of range; value clipped to lower limit", Some(DataOutOfRange(RangeKind::ClippedLower))),
+    (-222, "Data out of range; value clipped to upper limit", Some(DataOutOfRange(RangeKind::ClippedUpper))),
+    (-223, "Too much data", None),
+    (-224, "Illegal parameter value", None),
+    (-225, "Out of memory; measurement data overrun", None),
+    (-230, "Data corrupt or stale", None),
+    (-240, "Hardware error; CPU board initialization failed", Some(Hardware(HardwareKind::CpuInit))),
+    (-240, "Hardware error; GPIB interface failed", Some(Hardware(HardwareKind::Gpib))),
+    (-240, "Hardware error; cannot communicate with channel 3 hardware", Some(Hardware(HardwareKind::Ch3Comms))),
+    (-240, "Hardware error; cannot communicate with measurement hardware", Some(Hardware(HardwareKind::MeasComms))),
+    (-240, "Hardware error; channel 3 operation failed", Some(Hardware(HardwareKind::Ch3Operation))),
+    (-240, "Hardware error; measurement hardware initialization failed", Some(Hardware(HardwareKind::MeasInit))),
+    (-240, "Hardware error; measurement operation failed", Some(Hardware(HardwareKind::MeasOperation))),
+    (-240, "Hardware error; failed to program measurement FPGA security EEProm", Some(Hardware(HardwareKind::FpgaEeprom))),
+    (-241, "Hardware missing", None),
+    (-250, "Mass storage error: file read/write error", None),
+    (-252, "Missing media", None),
+    (-254, "Media full", Some(MediaFull)),
+    (-256, "File or folder name not found", None),
+    (-257, "File name error; invalid character in name", Some(FileName(FileNameKind::InvalidCharacter))),
+    (-257, "File name error; relative path not allowed", Some(FileName(FileNameKind::RelativePath))),
+    (-257, "File name error; path too long", Some(FileName(FileNameKind::PathTooLong))),
+    (-257, "File name error; path is a folder name", Some(FileName(FileNameKind::PathIsFolder))),
+    (-257, "File name error; not a folder name", Some(FileName(FileNameKind::NotFolder))),
+    (-257, "File name error; drive name missing or not recognized", Some(FileName(FileNameKind::BadDrive))),
+    (-257, "File name error; path name missing", Some(FileName(FileNameKind::MissingPath))),
+    (-257, "File name error; file or folder already exists", Some(FileName(FileNameKind::AlreadyExists))),
+    (-257, "File name error; folder not empty", Some(FileName(FileNameKind::FolderNotEmpty))),
+    (-257, "File name error; folder is default folder", Some(FileName(FileNameKind::DefaultFolder))),
+    (-257, "File name error; access denied", Some(FileName(FileNameKind::AccessDenied))),
+    (-257, "File name error", Some(FileName(FileNameKind::Generic))),
+    (-257, "File name error; file too large", Some(FileName(FileNameKind::FileTooLarge))),
+    (-257, "File name error; unknown file extension", Some(FileName(FileNameKind::UnknownExtension))),
 
     // Device-Specific Errors
-    (-310, "System error; internal software error"),
-    (-310, "System error; software initialization failed"),
-    (-310, "System error; out of memory"),
-    (-310, "System error; failed to erase calibration data in PIC EEProm"),
-    (-310, "System error; failed to erase system information in PIC EEProm"),
-    (-310, "System error; failed to read calibration information from PIC EEProm"),
-    (-310, "System error; failed to read system information from PIC EEProm"),
-    (-310, "System error; failed to write calibration information to PIC EEProm"),
-    (-310, "System error; failed to write system data to PIC EEProm"),
-    (-310, "System error; I2C Comms Failure, PIC:Ac Power Detect"),
-    (-310, "System error; I2C Comms Failure, PIC:BatteryFuelGauge"),
-    (-310, "System error; I2C Comms Failure, PIC:BatteryInfo"),
-    (-310, "System error; I2C Comms Failure, PIC:OCXO"),
-    (-310, "System error; I2C Comms Failure, PIC:PwrCondition"),
-    (-310, "System error; I2C Comms Failure, PIC:PwrOverVolt"),
-    (-310, "System error; I2C Comms Failure, PIC:PwrUnderVolt"),
-    (-310, "System error; I2C Comms Failure, PIC:SetOCXOStanby"),
-    (-310, "System error; I2C Comms Failure, PIC:Temperature"),
-    (-310, "System error; I2C Comms Failure, PIC:clearPwrCondition"),
-    (-310, "System error; I2C Comms Failure, PIC:cyclePower"),
-    (-310, "System error; I2C Comms Failure, PIC:finishPowerdown"),
-    (-310, "System error; I2C Comms Failure, PIC:picCommunication"),
-    (-310, "System error; I2C Comms Failure, PIC:setBattStorage"),
-    (-310, "System error; I2C Comms Failure, PIC:setBatteryPresent"),
-    (-310, "System error; PIC EEProm access failed"),
-    (-310, "System error; PIC EEProm failed waiting for unbusy"),
-    (-311, "Internal software error"),
-    (-313, "Calibration memory lost"),
-    (-313, "Calibration memory lost; memory corruption detected"),
-    (-313, "Calibration memory lost; due to firmware revision change"),
-    (-314, "Save/recall memory lost; memory corruption detected"),
-    (-314, "Save/recall memory lost; due to firmware revision change"),
-    (-315, "Configuration memory lost; memory corruption detected"),
-    (-315, "Configuration memory lost; due to firmware revision change"),
-    (-330, "Self-test failed"),
-    (-350, "Error queue overflow"),
+    (-310, "System error; internal software error", Some(System(SystemKind::InternalSoftware))),
+    (-310, "System error; software initialization failed", Some(System(SystemKind::InitFailed))),
+    (-310, "System error; out of memory", Some(System(SystemKind::OutOfMemory))),
+    (-310, "System error; failed to erase calibration data in PIC EEProm", Some(System(SystemKind::EraseCalibration))),
+    (-310, "System error; failed to erase system information in PIC EEProm", Some(System(SystemKind::EraseSystemInfo))),
+    (-310, "System error; failed to read calibration information from PIC EEProm", Some(System(SystemKind::ReadCalibration))),
+    (-310, "System error; failed to read system information from PIC EEProm", Some(System(SystemKind::ReadSystemInfo))),
+    (-310, "System error; failed to write calibration information to PIC EEProm", Some(System(SystemKind::WriteCalibration))),
+    (-310, "System error; failed to write system data to PIC EEProm", Some(System(SystemKind::WriteSystemInfo))),
+    (-310, "System error; I2C Comms Failure, PIC:Ac Power Detect", Some(System(SystemKind::I2cComms("Ac Power Detect")))),
+    (-310, "System error; I2C Comms Failure, PIC:BatteryFuelGauge", Some(System(SystemKind::I2cComms("BatteryFuelGauge")))),
+    (-310, "System error; I2C Comms Failure, PIC:BatteryInfo", Some(System(SystemKind::I2cComms("BatteryInfo")))),
+    (-310, "System error; I2C Comms Failure, PIC:OCXO", Some(System(SystemKind::I2cComms("OCXO")))),
+    (-310, "System error; I2C Comms Failure, PIC:PwrCondition", Some(System(SystemKind::I2cComms("PwrCondition")))),
+    (-310, "System error; I2C Comms Failure, PIC:PwrOverVolt", Some(System(SystemKind::I2cComms("PwrOverVolt")))),
+    (-310, "System error; I2C Comms Failure, PIC:PwrUnderVolt", Some(System(SystemKind::I2cComms("PwrUnderVolt")))),
+    (-310, "System error; I2C Comms Failure, PIC:SetOCXOStanby", Some(System(SystemKind::I2cComms("SetOCXOStanby")))),
+    (-310, "System error; I2C Comms Failure, PIC:Temperature", Some(System(SystemKind::I2cComms("Temperature")))),
+    (-310, "System error; I2C Comms Failure, PIC:clearPwrCondition", Some(System(SystemKind::I2cComms("clearPwrCondition")))),
+    (-310, "System error; I2C Comms Failure, PIC:cyclePower", Some(System(SystemKind::I2cComms("cyclePower")))),
+    (-310, "System error; I2C Comms Failure, PIC:finishPowerdown", Some(System(SystemKind::I2cComms("finishPowerdown")))),
+    (-310, "System error; I2C Comms Failure, PIC:picCommunication", Some(System(SystemKind::I2cComms("picCommunication")))),
+    (-310, "System error; I2C Comms Failure, PIC:setBattStorage", Some(System(SystemKind::I2cComms("setBattStorage")))),
+    (-310, "System error; I2C Comms Failure, PIC:setBatteryPresent", Some(System(SystemKind::I2cComms("setBatteryPresent")))),
+    (-310, "System error; PIC EEProm access failed", Some(System(SystemKind::EepromAccess))),
+    (-310, "System error; PIC EEProm failed waiting for unbusy", Some(System(SystemKind::EepromBusy))),
+    (-311, "Internal software error", None),
+    (-313, "Calibration memory lost", None),
+    (-313, "Calibration memory lost; memory corruption detected", None),
+    (-313, "Calibration memory lost; due to firmware revision change", None),
+    (-314, "Save/recall memory lost; memory corruption detected", None),
+    (-314, "Save/recall memory lost; due to firmware revision change", None),
+    (-315, "Configuration memory lost; memory corruption detected", None),
+    (-315, "Configuration memory lost; due to firmware revision change", None),
+    (-330, "Self-test failed", None),
+    (-350, "Error queue overflow", None),
 
     // Query Errors
-    (-410, "Query INTERRUPTED"),
-    (-420, "Query UNTERMINATED"),
-    (-430, "Query DEADLOCKED"),
-    (-440, "Query UNTERMINATED after indefinite response"),
+    (-410, "Query INTERRUPTED", None),
+    (-420, "Query UNTERMINATED", None),
+    (-430, "Query DEADLOCKED", None),
+    (-440, "Query UNTERMINATED after indefinite response", None),
 
     // Instrument Errors
-    (100, "Network Error"),
-    (110, "LXI mDNS Error"),
-    (201, "Memory lost: stored state"),
-    (202, "Memory lost: power-on state"),
-    (203, "Memory lost: stored measurements"),
-    (263, "Not able to execute while instrument is measuring"),
-    (291, "Not able to recall state: it is empty"),
-    (292, "State file size error"),
-    (293, "State file corrupt"),
-    (294, "Preference file size error"),
-    (295, "Preference file corrupt"),
-    (301, "Input termination protection relay opened"),
-    (302, "Cannot reset input protection; high voltage present"),
-    (305, "Not able to perform requested operation"),
-    (310, "Channel 3 pulse width too short"),
-    (311, "Channel 3 pulse width too long"),
-    (312, "Channel 3 pulse width could not be measured"),
-    (313, "Channel 3 burst frequency could not be measured"),
-    (314, "Channel 3 pulse ended before gate closed"),
-    (315, "Channel 3 power too high for operation"),
-    (316, "Channel 3 power too low for operation"),
-    (317, "Channel 3 power changed during measurement"),
-    (318, "Channel 3 input is not pulsed signal"),
-    (319, "Channel 3 frequency shift detected during measurement"),
-    (320, "Input signal frequency shift caused internal counter overflow"),
-    (321, "Measurement timeout occurred"),
-    (322, "Measurement overflow occurred"),
-    (514, "Not allowed"),
-    (514, "Not allowed; Instrument locked by another I/O session"),
-    (521, "Communications: input buffer overflow"),
-    (522, "Communications: output buffer overflow"),
-    (532, "Not able to achieve requested resolution"),
-    (540, "Cannot use overload as math reference"),
-    (541, "Cannot use zero as math reference for PCT, PPM, or PPB scaling functions"),
-    (550, "Not able to execute command in local mode"),
-    (580, "No valid external timebase"),
-    (600, "Internal licensing error"),
-    (601, "License file corrupt or empty"),
-    (602, "No valid licenses found for this instrument"),
-    (603, "Some licenses could not be installed"),
-    (604, "License not found"),
-    (800, "Nonvolatile memory write failure"),
-    (810, "State has not been stored"),
-    (820, "Model and Serial Numbers not restored"),
-    (821, "Controller and measurement board model numbers do not match"),
-    (822, "Controller and measurement board serial numbers do not match"),
+    (100, "Network Error", None),
+    (110, "LXI mDNS Error", None),
+    (201, "Memory lost: stored state", None),
+    (202, "Memory lost: power-on state", None),
+    (203, "Memory lost: stored measurements", None),
+    (263, "Not able to execute while instrument is measuring", None),
+    (291, "Not able to recall state: it is empty", None),
+    (292, "State file size error", None),
+    (293, "State file corrupt", None),
+    (294, "Preference file size error", None),
+    (295, "Preference file corrupt", None),
+    (301, "Input termination protection relay opened", None),
+    (302, "Cannot reset input protection; high voltage present", None),
+    (305, "Not able to perform requested operation", None),
+    (310, "Channel 3 pulse width too short", None),
+    (311, "Channel 3 pulse width too long", None),
+    (312, "Channel 3 pulse width could not be measured", None),
+    (313, "Channel 3 burst frequency could not be measured", None),
+    (314, "Channel 3 pulse ended before gate closed", None),
+    (315, "Channel 3 power too high for operation", None),
+    (316, "Channel 3 power too low for operation", None),
+    (317, "Channel 3 power changed during measurement", None),
+    (318, "Channel 3 input is not pulsed signal", None),
+    (319, "Channel 3 frequency shift detected during measurement", None),
+    (320, "Input signal frequency shift caused internal counter overflow", None),
+    (321, "Measurement timeout occurred", None),
+    (322, "Measurement overflow occurred", None),
+    (514, "Not allowed", Some(NotAllowed)),
+    (514, "Not allowed; Instrument locked by another I/O session", Some(Locked)),
+    (521, "Communications: input buffer overflow", None),
+    (522, "Communications: output buffer overflow", None),
+    (532, "Not able to achieve requested resolution", None),
+    (540, "Cannot use overload as math reference", None),
+    (541, "Cannot use zero as math reference for PCT, PPM, or PPB scaling functions", None),
+    (550, "Not able to execute command in local mode", None),
+    (580, "No valid external timebase", None),
+    (600, "Internal licensing error", None),
+    (601, "License file corrupt or empty", None),
+    (602, "No valid licenses found for this instrument", None),
+    (603, "Some licenses could not be installed", None),
+    (604, "License not found", None),
+    (800, "Nonvolatile memory write failure", None),
+    (810, "State has not been stored", None),
+    (820, "Model and Serial Numbers not restored", None),
+    (821, "Controller and measurement board model numbers do not match", None),
+    (822, "Controller and measurement board serial numbers do not match", None),
 
     // Self-Test Errors
-    (901, "Self Test failed; auto-calibration failure"),
-    (902, "Self-test failed; main CPU power supply out of range"),
-    (903, "Self-test failed; real time clock settings lost"),
-    (904, "Self-test failed; main CPU error accessing boot environment"),
-    (905, "Self-test failed; failed to read FPGA revision"),
-    (906, "Self-test failed; FPGA revision is less than expected"),
-    (907, "Self-test failed; PIC communication failure"),
-    (908, "Self-test failed; battery test failed"),
-    (909, "Self-test failed; GPIB test failed"),
-    (910, "Self-test failed; channel 3 test failed"),
-    (911, "Self-test failed; front panel revision check failed"),
-    (912, "Self-test failed; measurement board test failed"),
+    (901, "Self Test failed; auto-calibration failure", None),
+    (902, "Self-test failed; main CPU power supply out of range", None),
+    (903, "Self-test failed; real time clock settings lost", None),
+    (904, "Self-test failed; main CPU error accessing boot environment", None),
+    (905, "Self-test failed; failed to read FPGA revision", None),
+    (906, "Self-test failed; FPGA revision is less than expected", None),
+    (907, "Self-test failed; PIC communication failure", None),
+    (908, "Self-test failed; battery test failed", None),
+    (909, "Self-test failed; GPIB test failed", None),
+    (910, "Self-test failed; channel 3 test failed", None),
+    (911, "Self-test failed; front panel revision check failed", None),
+    (912, "Self-test failed; measurement board test failed", None),
 
     // Calibration Errors
-    (701, "Calibration error; security defeated"),
-    (702, "Calibration error; calibration memory is secured"),
-    (703, "Calibration error; secure code provided was invalid"),
-    (704, "Calibration error: secure code too long"),
-    (705, "Calibration error; calibration aborted"),
-    (706, "Calibration error: provided value out of range"),
-    (707, "Calibration error: computed correction factor out of range"),
-    (708, "Calibration error: signal measurement out of range"),
-    (709, "Calibration error: no calibration for this function"),
-    (710, "Calibration error: full scale correction out of range"),
-    (711, "Calibration error: calibration string too long"),
-    (712, "Calibration failed"),
-    (713, "Channel 3 calibration signal not detected"),
-    (714, "Channel 3 calibration signal power level error"),
-    (715, "Channel 3 calibration signal frequency error"),
-    (716, "Channel 3 calibration signal is not CW"),
-    (717, "Channel 3 calibration timeout occurred"),
-    (720, "Auto-calibration failed; input signal detected"),
-    (740, "Calibration data lost: secure state"),
-    (740, "Calibration information lost: count, security state, security code, string"),
-    (741, "Calibration data lost: string data"),
-    (742, "Calibration data lost: corrections"),
-    (748, "Calibration memory write failure"),
-    (750, "Calibration data not restored"),
+    (701, "Calibration error; security defeated", None),
+    (702, "Calibration error; calibration memory is secured", None),
+    (703, "Calibration error; secure code provided was invalid", None),
+    (704, "Calibration error: secure code too long", None),
+    (705, "Calibration error; calibration aborted", None),
+    (706, "Calibration error: provided value out of range", None),
+    (707, "Calibration error: computed correction factor out of range", None),
+    (708, "Calibration error: signal measurement out of range", None),
+    (709, "Calibration error: no calibration for this function", None),
+    (710, "Calibration error: full scale correction out of range", None),
+    (711, "Calibration error: calibration string too long", None),
+    (712, "Calibration failed", None),
+    (713, "Channel 3 calibration signal not detected", None),
+    (714, "Channel 3 calibration signal power level error", None),
+    (715, "Channel 3 calibration signal frequency error", None),
+    (716, "Channel 3 calibration signal is not CW", None),
+    (717, "Channel 3 calibration timeout occurred", None),
+    (720, "Auto-calibration failed; input signal detected", None),
+    (740, "Calibration data lost: secure state", None),
+    (740, "Calibration information lost: count, security state, security code, string", None),
+    (741, "Calibration data lost: string data", None),
+    (742, "Calibration data lost: corrections", None),
+    (748, "Calibration memory write failure", None),
+    (750, "Calibration data not restored", None),
 ];
 
+/// Specific error for codes that have several documented messages.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum KsDevErrVariant {
+    /// -123
+    ExponentTooLarge,
+    /// -123
+    NumericOverflow,
+    /// -144
+    CharacterDataTooLong,
+    /// -151
+    InvalidString,
+    /// -221
+    SettingsConflict(ConflictKind),
+    /// -222
+    DataOutOfRange(RangeKind),
+    /// -240
+    Hardware(HardwareKind),
+    /// -254
+    MediaFull,
+    /// -257
+    FileName(FileNameKind),
+    /// -310
+    System(SystemKind),
+    /// 514
+    NotAllowed,
+    /// 514
+    Locked,
+}
+
+/// -221 "Settings conflict" messages
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ConflictKind {
+    Generic,
+    TriggerNotBus,
+    ScaleZeroReference,
+    CalcDisabled,
+    HistogramDisabled,
+    StartDelayEventsCh3,
+    StopHoldoffEventsCh3,
+    RoscCheckNotExternal,
+    TimestampSampleCount,
+    FreqGateOnMeasChannel,
+    ContinuousModeFunction,
+    ExtGateOnMeasChannel,
+    TintGateOnMeasChannel,
+    TotGateOnMeasChannel,
+    AutoLevelGateChannel,
+    DeleteAutoRecallState,
+    TintBasebandGate,
+    ImmediateGateStop,
+    ContinuousTotalizeRead,
+    ExtGateOutput,
+    HistogramZeroWidth,
+    HistogramRange,
+    FreqInfiniteHoldoff,
+    TintInfiniteHoldoff,
+    RangeProbe,
+    ThresholdRange,
+    ReferenceOrder,
+    ReferenceOrderReset,
+    LimitOrder,
+    ReferenceUnits,
+    StopHoldoffMin,
+    TriggerSourceBus,
+}
+
+/// -222 "Data out of range" messages
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum RangeKind {
+    Generic,
+    ClippedLower,
+    ClippedUpper,
+}
+
+/// -240 "Hardware error" messages
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum HardwareKind {
+    CpuInit,
+    Gpib,
+    Ch3Comms,
+    MeasComms,
+    Ch3Operation,
+    MeasInit,
+    MeasOperation,
+    FpgaEeprom,
+}
+
+/// -257 "File name error" messages
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum FileNameKind {
+    Generic,
+    InvalidCharacter,
+    RelativePath,
+    PathTooLong,
+    PathIsFolder,
+    NotFolder,
+    BadDrive,
+    MissingPath,
+    AlreadyExists,
+    FolderNotEmpty,
+    DefaultFolder,
+    AccessDenied,
+    FileTooLarge,
+    UnknownExtension,
+}
+
+/// -310 "System error" messages
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum SystemKind {
+    InternalSoftware,
+    InitFailed,
+    OutOfMemory,
+    EraseCalibration,
+    EraseSystemInfo,
+    ReadCalibration,
+    ReadSystemInfo,
+    WriteCalibration,
+    WriteSystemInfo,
+    /// I2C communication failure, contains PIC function name
+    I2cComms(&'static str),
+    EepromAccess,
+    EepromBusy,
+}
+
+impl KsDevErrVariant {
+    pub fn code(&self) -> i32 {
+        match self {
+            ExponentTooLarge | NumericOverflow => -123,
+            CharacterDataTooLong => -144,
+            InvalidString => -151,
+            SettingsConflict(_) => -221,
+            DataOutOfRange(_) => -222,
+            Hardware(_) => -240,
+            MediaFull => -254,
+            FileName(_) => -257,
+            System(_) => -310,
+            NotAllowed | Locked => 514,
+        }
+    }
+}
+
+type DevErrEntry = (i32, &'static str, Option<KsDevErrVariant>);
+
 lazy_static! {
-    static ref DEV_ERR_MAP: HashMap<i32, &'static str> = {
-        let mut m = HashMap::with_capacity(DEV_ERR_LIST.len());
-        for (code, text) in DEV_ERR_LIST.iter() {
-            m.insert(*code, *text);
+    static ref DEV_ERR_MAP: HashMap<i32, Vec<&'static DevErrEntry>> = {
+        let mut m = HashMap::<i32, Vec<&'static DevErrEntry>>::with_capacity(DEV_ERR_LIST.len());
+        for entry in DEV_ERR_LIST.iter() {
+            m.entry(entry.0).or_default().push(entry);
         }
         m.shrink_to_fit();
         m
     };
 }
 
+/// Finds the entry matching device message exactly or, failing that,
+/// the longest entry the message starts with.
+fn find_entry(entries: &[&'static DevErrEntry], message: &str) -> Option<&'static DevErrEntry> {
+    let message = message.trim();
+    entries.iter()
+    .find(|e| e.1.eq_ignore_ascii_case(message))
+    .or_else(|| {
+        entries.iter()
+        .filter(|e| {
+            message.len() >= e.1.len() &&
+            message.is_char_boundary(e.1.len()) &&
+            message[..e.1.len()].eq_ignore_ascii_case(e.1)
+        })
+        .max_by_key(|e| e.1.len())
+    })
+    .cloned()
+}
+
+/// Error class according to SCPI code ranges.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum KsDevErrKind {
+    Command,
+    Execution,
+    DeviceSpecific,
+    Query,
+    Instrument,
+    SelfTest,
+    Calibration,
+}
+
+impl KsDevErrKind {
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            -199 ..= -100 => KsDevErrKind::Command,
+            -299 ..= -200 => KsDevErrKind::Execution,
+            -399 ..= -300 => KsDevErrKind::DeviceSpecific,
+            -499 ..= -400 => KsDevErrKind::Query,
+            700 ..= 799 => KsDevErrKind::Calibration,
+            900 ..= 999 => KsDevErrKind::SelfTest,
+            _ => KsDevErrKind::Instrument,
+        }
+    }
+}
+
 #[derive(Clone, Eq, PartialEq)]
 pub struct KsDevErr {
     code: i32,
+    message: Option<String>,
 }
 
 impl KsDevErr {
-    pub fn new(code: i32) -> Option<Self> {
-        if DEV_ERR_MAP.contains_key(&code) {
-            Some(KsDevErr { code })
-        } else {
-            None
-        }
+    pub fn new(code: i32) -> Self {
+        KsDevErr { code, message: None }
+    }
+
+    /// Creates error keeping the message text reported by the device.
+    pub fn with_message(code: i32, message: &str) -> Self {
+        KsDevErr { code, message: Some(String::from(message)) }
     }
 
     pub fn code(&self) -> i32 {
         self.code
     }
+
+    pub fn kind(&self) -> KsDevErrKind {
+        KsDevErrKind::from_code(self.code)
+    }
+
+    /// Whether the code is listed in the device documentation.
+    pub fn is_known(&self) -> bool {
+        DEV_ERR_MAP.contains_key(&self.code)
+    }
+
+    fn entry(&self) -> Option<&'static DevErrEntry> {
+        let entries = DEV_ERR_MAP.get(&self.code)?;
+        match &self.message {
+            Some(m) => find_entry(entries, m).or_else(|| entries.first().cloned()),
+            None => entries.first().cloned(),
+        }
+    }
+
+    /// Specific error matched by the device message.
+    pub fn variant(&self) -> Option<KsDevErrVariant> {
+        let entries = DEV_ERR_MAP.get(&self.code)?;
+        self.message.as_ref()
+        .and_then(|m| find_entry(entries, m))
+        .and_then(|e| e.2)
+    }
+
+    /// Documented text for the code, or the device message for unknown codes.
+    pub fn description(&self) -> &str {
+        match self.entry() {
+            Some(e) => e.1,
+            None => self.message.as_deref().unwrap_or("Unknown error"),
+        }
+    }
+
+    /// Message reported by the device, or the documented text for the code.
+    pub fn message(&self) -> &str {
+        match &self.message {
+            Some(m) => m,
+            None => self.description(),
+        }
+    }
+}
+
+impl From<KsDevErrVariant> for KsDevErr {
+    fn from(var: KsDevErrVariant) -> Self {
+        match DEV_ERR_LIST.iter().find(|e| e.2 == Some(var)) {
+            Some(e) => KsDevErr::with_message(e.0, e.1),
+            None => KsDevErr::new(var.code()),
+        }
+    }
 }
 
 impl fmt::Debug for KsDevErr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}: '{}'", self.code, DEV_ERR_MAP.get(&self.code).unwrap())
+        write!(f, "{}: '{}'", self.code, self.message())
     }
 }
 
 impl fmt::Display for KsDevErr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", DEV_ERR_MAP.get(&self.code).unwrap())
+        write!(f, "{}", self.message())
     }
 }
 
 impl error::Error for KsDevErr {}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind() {
+        assert_eq!(KsDevErr::new(-113).kind(), KsDevErrKind::Command);
+        assert_eq!(KsDevErr::new(-221).kind(), KsDevErrKind::Execution);
+        assert_eq!(KsDevErr::new(-310).kind(), KsDevErrKind::DeviceSpecific);
+        assert_eq!(KsDevErr::new(-410).kind(), KsDevErrKind::Query);
+        assert_eq!(KsDevErr::new(514).kind(), KsDevErrKind::Instrument);
+        assert_eq!(KsDevErr::new(905).kind(), KsDevErrKind::SelfTest);
+        assert_eq!(KsDevErr::new(712).kind(), KsDevErrKind::Calibration);
+    }
+
+    #[test]
+    fn variant() {
+        let e = KsDevErr::with_message(-221, "Settings conflict; cannot use READ? with continuous totalize");
+        assert_eq!(e.variant(), Some(SettingsConflict(ConflictKind::ContinuousTotalizeRead)));
+        assert_eq!(KsDevErr::with_message(-123, "Numeric overflow").variant(), Some(NumericOverflow));
+        assert_eq!(KsDevErr::with_message(-221, "Settings conflict; new one").variant(), Some(SettingsConflict(ConflictKind::Generic)));
+        assert_eq!(KsDevErr::new(-221).variant(), None);
+    }
+
+    #[test]
+    fn from_variant() {
+        let e = KsDevErr::from(SettingsConflict(ConflictKind::TriggerNotBus));
+        assert_eq!(e.code(), -221);
+        assert_eq!(e.variant(), Some(SettingsConflict(ConflictKind::TriggerNotBus)));
+        assert_eq!(e.to_string(), "Settings conflict; *TRG when TRIG:SOUR BUS not selected; trigger ignored");
+    }
+
+    #[test]
+    fn unknown_code() {
+        let e = KsDevErr::with_message(-999, "Something new");
+        assert!(!e.is_known());
+        assert_eq!(e.description(), "Something new");
+        assert_eq!(KsDevErr::new(-999).description(), "Unknown error");
+    }
+}
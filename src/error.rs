@@ -1,10 +1,48 @@
 use std::io;
 use std::error;
 use std::fmt;
+use std::time::{Duration};
 
 use crate::{KsDevErr};
 use crate::format::{DTError, ParseError};
 
+
+/// Device exchange during which an error occured.
+#[derive(Debug, Clone)]
+pub struct Context {
+    /// Command sent to device
+    pub command: String,
+    /// Raw device response, if it was received but could not be handled
+    pub response: Option<Vec<u8>>,
+    /// Time passed from sending the command until the failure
+    pub elapsed: Duration,
+    /// Device errors queued after the reported one
+    pub errors: Vec<KsDevErr>,
+}
+
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' after {:?}", self.command, self.elapsed)?;
+        if let Some(resp) = &self.response {
+            write!(f, ", response {:?}", String::from_utf8_lossy(resp))?;
+        }
+        if !self.errors.is_empty() {
+            let codes: Vec<String> = self.errors.iter().map(|e| e.code().to_string()).collect();
+            write!(f, ", followed by {}", codes.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Crate error.
+///
+/// Errors returned by `KsFc` methods, except `connect`, are always wrapped
+/// in `Error::Command` with the context of the command they belong to,
+/// so match on `root()` instead of the error itself, e.g.
+/// `matches!(err.root(), Error::Dev(_))`, or use `dev()` and `locked_owner()`.
+/// This includes timeouts, connection failures, waits that expired and
+/// checks done before the command is sent, the latter have no response.
+/// Standalone checks like `LanConfig::validate` return bare errors.
 #[derive(Debug)]
 pub enum Error {
     Io(io::Error),
@@ -12,15 +50,115 @@ pub enum Error {
     Parse(ParseError),
     DataType(DTError),
     Other(String),
+    Command(Box<Error>, Context),
+    /// Device did not reply in time, but connection is still alive
+    Timeout { command: String, waited: Duration },
+    /// Awaited event did not occur in time, repeating the command would not help
+    WaitTimeout { event: String, waited: Duration },
+    /// Connection was closed by device or network
+    Disconnected,
+    /// Command was issued without connection
+    NotConnected,
+    /// Device is locked by another I/O session, e.g. `LAN10.0.0.5`
+    Locked { owner: String },
+}
+
+fn is_timeout(e: &io::Error) -> bool {
+    matches!(e.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock)
+}
+
+impl Error {
+    /// Attaches command context to the error unless it already has one.
+    ///
+    /// I/O timeouts are turned into `Error::Timeout` for the command.
+    pub fn with_context(self, ctx: Context) -> Error {
+        match self {
+            Error::Command(..) => self,
+            Error::Io(ref e) if is_timeout(e) => {
+                let err = Error::Timeout {
+                    command: ctx.command.clone(),
+                    waited: ctx.elapsed,
+                };
+                Error::Command(Box::new(err), ctx)
+            },
+            e => Error::Command(Box::new(e), ctx),
+        }
+    }
+
+    /// Error without command context.
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::Command(e, _) => e.root(),
+            e => e,
+        }
+    }
+
+    /// Strips command context from the error.
+    pub fn into_root(self) -> Error {
+        match self {
+            Error::Command(e, _) => e.into_root(),
+            e => e,
+        }
+    }
+
+    pub fn context(&self) -> Option<&Context> {
+        match self {
+            Error::Command(_, ctx) => Some(ctx),
+            _ => None,
+        }
+    }
+
+    /// Device error, if the error was reported by device.
+    pub fn dev(&self) -> Option<&KsDevErr> {
+        match self.root() {
+            Error::Dev(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Owner of the lock, if the command failed because device is locked.
+    pub fn locked_owner(&self) -> Option<&str> {
+        match self.root() {
+            Error::Locked { owner } => Some(owner),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::Dev(e) => write!(f, "Device error {}: {}", e.code(), e),
+            Error::Parse(e) => write!(f, "Parse error: {}", e),
+            Error::DataType(e) => write!(f, "Data type error: {}", e),
+            Error::Other(s) => write!(f, "{}", s),
+            Error::Command(e, ctx) => write!(f, "{} ({})", e, ctx),
+            Error::Timeout { command, waited } => {
+                write!(f, "No reply to '{}' in {:?}", command, waited)
+            },
+            Error::WaitTimeout { event, waited } => {
+                write!(f, "{} not seen in {:?}", event, waited)
+            },
+            Error::Disconnected => write!(f, "Connection lost"),
+            Error::NotConnected => write!(f, "Not connected"),
+            Error::Locked { owner } => write!(f, "Instrument locked by {}", owner),
+        }
     }
 }
 
-impl error::Error for Error {}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Dev(e) => Some(e),
+            Error::Parse(e) => Some(e),
+            Error::DataType(e) => Some(e),
+            Error::Command(e, _) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
 
 macro_rules! impl_from {
     ($src:ty, $opt:ident) => {
@@ -32,7 +170,18 @@ macro_rules! impl_from {
     };
 }
 
-impl_from!(io::Error, Io);
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        match err.kind() {
+            io::ErrorKind::NotConnected => Error::NotConnected,
+            io::ErrorKind::ConnectionReset |
+            io::ErrorKind::ConnectionAborted |
+            io::ErrorKind::BrokenPipe |
+            io::ErrorKind::UnexpectedEof => Error::Disconnected,
+            _ => Error::Io(err),
+        }
+    }
+}
 impl_from!(KsDevErr, Dev);
 impl_from!(ParseError, Parse);
 impl_from!(DTError, DataType);
@@ -44,3 +193,75 @@ impl From<&str> for Error {
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_context() {
+        let err = Error::from(ParseError::Arg(1, "x".parse::<i32>().unwrap_err().into()))
+        .with_context(Context {
+            command: String::from("TEST?"),
+            response: Some(b"1,x".to_vec()),
+            elapsed: Duration::from_millis(5),
+            errors: Vec::new(),
+        });
+        assert_eq!(
+            err.to_string(),
+            "Parse error: value #1: invalid digit found in string \
+             ('TEST?' after 5ms, response \"1,x\")",
+        );
+        assert!(err.root().context().is_none());
+    }
+
+    #[test]
+    fn device_error_root() {
+        let ctx = Context {
+            command: String::from("TRIG:COUN 0"),
+            response: None,
+            elapsed: Duration::from_millis(1),
+            errors: vec![KsDevErr::new(-113)],
+        };
+        let err = Error::from(KsDevErr::new(-222)).with_context(ctx.clone());
+        assert!(matches!(err.root(), Error::Dev(_)));
+        assert_eq!(err.dev().unwrap().code(), -222);
+        assert_eq!(err.context().unwrap().command, "TRIG:COUN 0");
+        assert_eq!(err.context().unwrap().errors[0].code(), -113);
+        assert!(err.to_string().ends_with(", followed by -113)"));
+
+        let err = Error::Locked { owner: String::from("LAN10.0.0.5") }.with_context(ctx);
+        assert_eq!(err.locked_owner(), Some("LAN10.0.0.5"));
+        assert!(err.dev().is_none());
+    }
+
+    #[test]
+    fn timeout() {
+        let err = Error::from(io::Error::from(io::ErrorKind::TimedOut))
+        .with_context(Context {
+            command: String::from("FETC?"),
+            response: None,
+            elapsed: Duration::from_secs(2),
+            errors: Vec::new(),
+        });
+        assert_eq!(err.context().unwrap().command, "FETC?");
+        match err.root() {
+            Error::Timeout { command, waited } => {
+                assert_eq!(command, "FETC?");
+                assert_eq!(*waited, Duration::from_secs(2));
+            },
+            e => panic!("{:?}", e),
+        }
+        assert!(err.is_retryable());
+        let err = Error::WaitTimeout {
+            event: String::from("Status event"),
+            waited: Duration::from_secs(2),
+        };
+        assert!(!err.is_retryable());
+        match Error::from(io::Error::from(io::ErrorKind::BrokenPipe)) {
+            Error::Disconnected => (),
+            e => panic!("{:?}", e),
+        }
+    }
+}
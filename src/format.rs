@@ -14,6 +14,17 @@ pub enum DTError {
     NotBin,
 }
 
+impl fmt::Display for DTError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DTError::NotText => write!(f, "text data expected"),
+            DTError::NotBin => write!(f, "binary data expected"),
+        }
+    }
+}
+
+impl Error for DTError {}
+
 pub fn into_text(data: KsData) -> crate::Result<String> {
     data.into_text().ok_or(DTError::NotText.into())
 }
@@ -22,6 +33,14 @@ pub fn into_bin(data: KsData) -> crate::Result<Vec<u8>> {
     data.into_bin().ok_or(DTError::NotBin.into())
 }
 
+/// Makes IEEE 488.2 definite-length block `#<n><length><data>`.
+pub fn bin_block(data: &[u8]) -> Vec<u8> {
+    let len = data.len().to_string();
+    let mut block = format!("#{}{}", len.len(), len).into_bytes();
+    block.extend_from_slice(data);
+    block
+}
+
 // conversions
 
 pub fn secs_as_dur(sec: f64) -> Option<Duration> {
@@ -49,14 +68,17 @@ pub enum ParseError {
     EndOfString,
     TooFewArgs,
     Arg(usize, Box<dyn Error + Send + Sync>),
+    Value(String),
 }
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            ParseError::Parse(e) => write!(f, "ParseError::Parse({})", e),
-            ParseError::Arg(n, e) => write!(f, "ParseError::Arg({}, {})", n, e),
-            _ => write!(f, "{:?}", self),
+            ParseError::Parse(e) => write!(f, "{}", e),
+            ParseError::EndOfString => write!(f, "unexpected end of string"),
+            ParseError::TooFewArgs => write!(f, "more values than expected"),
+            ParseError::Arg(n, e) => write!(f, "value #{}: {}", n, e),
+            ParseError::Value(v) => write!(f, "unexpected value '{}'", v),
         }
     }
 }
@@ -68,7 +90,101 @@ where T::Err: 'static + Error + Send + Sync {
     text.parse::<T>().map_err(|e| ParseError::Parse(e.into()))
 }
 
-#[macro_use]
+/// Parses SCPI boolean, either `0|1` or `OFF|ON`.
+pub fn parse_bool(text: &str) -> Result<bool, ParseError> {
+    match text.trim() {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        t if t.eq_ignore_ascii_case("OFF") => Ok(false),
+        t if t.eq_ignore_ascii_case("ON") => Ok(true),
+        t => Err(ParseError::Value(String::from(t))),
+    }
+}
+
+/// Formats SCPI boolean.
+pub fn bool_as_str(value: bool) -> &'static str {
+    if value { "ON" } else { "OFF" }
+}
+
+/// Parses SCPI character data by the list of short mnemonics.
+///
+/// Both short and long forms of reply are accepted.
+pub fn parse_mnemonic<T: Copy>(text: &str, list: &[(&str, T)]) -> Result<T, ParseError> {
+    let text = text.trim();
+    list.iter()
+    .find(|(m, _)| {
+        text.len() >= m.len() &&
+        text.is_char_boundary(m.len()) &&
+        text[..m.len()].eq_ignore_ascii_case(m)
+    })
+    .map(|(_, v)| *v)
+    .ok_or_else(|| ParseError::Value(String::from(text)))
+}
+
+/// Parses SCPI string data, removing quotes and unescaping doubled quotes.
+pub fn parse_string(text: &str) -> Result<String, ParseError> {
+    let text = text.trim();
+    match text.chars().next() {
+        Some(q) if q == '"' || q == '\'' => {
+            if text.len() < 2 || !text.ends_with(q) {
+                return Err(ParseError::EndOfString);
+            }
+            let mut qq = String::new();
+            qq.push(q);
+            qq.push(q);
+            Ok(text[1..(text.len() - 1)].replace(&qq, &q.to_string()))
+        },
+        _ => Ok(String::from(text)),
+    }
+}
+
+/// Makes SCPI string parameter, inverse of `parse_string`.
+pub fn quote_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+// parsing yielding crate error, used as `Batch::query_with` parsers
+
+pub(crate) fn parse_flag(text: &str) -> crate::Result<bool> {
+    parse_bool(text).map_err(|e| e.into())
+}
+
+pub(crate) fn parse_float(text: &str) -> crate::Result<f64> {
+    parse::<f64>(text).map_err(|e| e.into())
+}
+
+/// Parses seconds into `Duration`.
+pub(crate) fn parse_secs(text: &str) -> crate::Result<Duration> {
+    parse_float(text)
+    .and_then(|s| secs_as_dur(s).ok_or_else(|| "Negative duration".into()))
+}
+
+pub(crate) fn parse_text(text: &str) -> crate::Result<String> {
+    parse_string(text).map_err(|e| e.into())
+}
+
+/// Splits text by `sep` ignoring separators inside quoted strings.
+pub fn split_unquoted(text: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quote = None;
+    let mut begin = 0;
+    for (i, c) in text.char_indices() {
+        match quote {
+            Some(q) => if c == q {
+                quote = None;
+            },
+            None => if c == '"' || c == '\'' {
+                quote = Some(c);
+            } else if c == sep {
+                parts.push(&text[begin..i]);
+                begin = i + c.len_utf8();
+            },
+        }
+    }
+    parts.push(&text[begin..]);
+    parts
+}
+
 macro_rules! parse {
     ( $s:expr, $( $x:ty ),*) => {{
         let parse_fn = |text: &str| -> Result<( $( $x, )* ), crate::format::ParseError> {
@@ -96,11 +212,41 @@ macro_rules! parse {
 mod tests {
     #[test]
     fn parse_ints() {
-        assert_eq!(parse!(&"-1,+2,+3"[..], i32, i32, i32).unwrap(), (-1, 2, 3))
+        assert_eq!(parse!("-1,+2,+3", i32, i32, i32).unwrap(), (-1, 2, 3))
     }
 
     #[test]
     fn parse_float() {
-        assert_eq!(parse!(&"+9.91000000000000E+37"[..], f64).unwrap(), (9.91e37,))
+        assert_eq!(parse!("+9.91000000000000E+37", f64).unwrap(), (9.91e37,))
+    }
+
+    #[test]
+    fn split_replies() {
+        assert_eq!(
+            super::split_unquoted("+1;\"a;b\";-221,\"Settings conflict; x\"", ';'),
+            vec!["+1", "\"a;b\"", "-221,\"Settings conflict; x\""],
+        )
+    }
+
+    #[test]
+    fn parse_quoted() {
+        assert_eq!(super::parse_string(" \"say \"\"hi\"\"\"\n").unwrap(), "say \"hi\"")
+    }
+
+    #[test]
+    fn block() {
+        assert_eq!(super::bin_block(b"abc"), b"#13abc");
+        assert_eq!(&super::bin_block(&[0; 12])[..4], b"#212");
+
+        // Block header is already stripped by transport, payload is kept as is
+        let data = ks_lxi::KsData::Bin(b"#13abc".to_vec());
+        assert_eq!(super::into_bin(data).unwrap(), b"#13abc");
+    }
+
+    #[test]
+    fn quote() {
+        let text = "a \"b\"";
+        assert_eq!(super::quote_string(text), "\"a \"\"b\"\"\"");
+        assert_eq!(super::parse_string(&super::quote_string(text)).unwrap(), text);
     }
 }
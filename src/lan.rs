@@ -0,0 +1,212 @@
+use std::net::{Ipv4Addr};
+use std::time::{Instant};
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use crate::{KsFc, KsDevErr};
+use crate::deverr::{KsDevErrVariant, RangeKind};
+use crate::constants::{HOSTNAME_MAX_LEN};
+use crate::format::{parse, parse_flag, parse_string, parse_text, quote_string, bool_as_str};
+
+
+/// LAN settings, `SYSTem:COMMunicate:LAN` subsystem.
+///
+/// Addresses are the static ones, used when DHCP is off or fails.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LanConfig {
+    pub dhcp: bool,
+    pub address: Ipv4Addr,
+    pub subnet_mask: Ipv4Addr,
+    pub gateway: Ipv4Addr,
+    /// Primary and secondary DNS servers
+    pub dns: [Ipv4Addr; 2],
+    /// Primary and secondary WINS servers
+    pub wins: [Ipv4Addr; 2],
+    pub hostname: String,
+    /// Telnet session prompt
+    pub telnet_prompt: String,
+    /// Telnet session welcome message
+    pub telnet_welcome: String,
+}
+
+fn parse_addr(text: &str) -> crate::Result<Ipv4Addr> {
+    parse_string(text)
+    .and_then(|text| parse::<Ipv4Addr>(&text))
+    .map_err(|e| e.into())
+}
+
+fn out_of_range() -> crate::Error {
+    crate::Error::Dev(KsDevErr::from(KsDevErrVariant::DataOutOfRange(RangeKind::Generic)))
+}
+
+/// Checks hostname, letters, digits and inner hyphens only.
+fn check_hostname(name: &str) -> crate::Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('-') && !name.ends_with('-')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !valid {
+        Err(crate::Error::Dev(KsDevErr::from(KsDevErrVariant::InvalidString)))
+    } else if name.len() > HOSTNAME_MAX_LEN {
+        Err(crate::Error::Dev(KsDevErr::from(KsDevErrVariant::CharacterDataTooLong)))
+    } else {
+        Ok(())
+    }
+}
+
+impl LanConfig {
+    /// Checks settings which would leave the device unreachable.
+    ///
+    /// Subnet mask must be contiguous and not empty, static gateway, if set,
+    /// must be in the subnet of static address. Hostname is at most
+    /// `HOSTNAME_MAX_LEN` letters, digits and hyphens.
+    pub fn validate(&self) -> crate::Result<()> {
+        let mask = u32::from(self.subnet_mask);
+        if mask == 0 || mask.leading_ones() + mask.trailing_zeros() != 32 {
+            return Err(out_of_range());
+        }
+        if !self.dhcp {
+            if self.address.is_unspecified() || self.address.is_broadcast() {
+                return Err(out_of_range());
+            }
+            let gateway = u32::from(self.gateway);
+            if !self.gateway.is_unspecified() && gateway & mask != u32::from(self.address) & mask {
+                return Err(out_of_range());
+            }
+        }
+        check_hostname(&self.hostname)
+    }
+
+    /// Commands staging the settings, not including `SYST:COMM:LAN:UPD`.
+    pub fn commands(&self) -> Vec<String> {
+        let mut cmds = vec![
+            format!("SYST:COMM:LAN:DHCP {}", bool_as_str(self.dhcp)),
+            format!("SYST:COMM:LAN:IPAD {}", quote_string(&self.address.to_string())),
+            format!("SYST:COMM:LAN:SMAS {}", quote_string(&self.subnet_mask.to_string())),
+            format!("SYST:COMM:LAN:GAT {}", quote_string(&self.gateway.to_string())),
+        ];
+        for (i, addr) in self.dns.iter().enumerate() {
+            cmds.push(format!("SYST:COMM:LAN:DNS{} {}", i + 1, quote_string(&addr.to_string())));
+        }
+        for (i, addr) in self.wins.iter().enumerate() {
+            cmds.push(format!("SYST:COMM:LAN:WINS{} {}", i + 1, quote_string(&addr.to_string())));
+        }
+        cmds.push(format!("SYST:COMM:LAN:HOST {}", quote_string(&self.hostname)));
+        cmds.push(format!("SYST:COMM:LAN:TELN:PROM {}", quote_string(&self.telnet_prompt)));
+        cmds.push(format!("SYST:COMM:LAN:TELN:WMES {}", quote_string(&self.telnet_welcome)));
+        cmds
+    }
+}
+
+impl KsFc {
+    /// Reads static LAN settings.
+    pub fn lan_config(&mut self) -> crate::Result<LanConfig> {
+        self.idempotent(|fc| {
+            let mut batch = fc.batch();
+            let dhcp = batch.query_with("SYST:COMM:LAN:DHCP?", parse_flag);
+            let address = batch.query_with("SYST:COMM:LAN:IPAD? STAT", parse_addr);
+            let subnet_mask = batch.query_with("SYST:COMM:LAN:SMAS? STAT", parse_addr);
+            let gateway = batch.query_with("SYST:COMM:LAN:GAT? STAT", parse_addr);
+            let dns = [1, 2].map(|i| batch.query_with(&format!("SYST:COMM:LAN:DNS{}? STAT", i), parse_addr));
+            let wins = [1, 2].map(|i| batch.query_with(&format!("SYST:COMM:LAN:WINS{}? STAT", i), parse_addr));
+            let hostname = batch.query_with("SYST:COMM:LAN:HOST?", parse_text);
+            let telnet_prompt = batch.query_with("SYST:COMM:LAN:TELN:PROM?", parse_text);
+            let telnet_welcome = batch.query_with("SYST:COMM:LAN:TELN:WMES?", parse_text);
+            let reply = batch.send()?;
+
+            Ok(LanConfig {
+                dhcp: reply.get(dhcp)?,
+                address: reply.get(address)?,
+                subnet_mask: reply.get(subnet_mask)?,
+                gateway: reply.get(gateway)?,
+                dns: [reply.get(dns[0])?, reply.get(dns[1])?],
+                wins: [reply.get(wins[0])?, reply.get(wins[1])?],
+                hostname: reply.get(hostname)?,
+                telnet_prompt: reply.get(telnet_prompt)?,
+                telnet_welcome: reply.get(telnet_welcome)?,
+            })
+        })
+    }
+
+    /// Stages LAN settings and activates them with `SYST:COMM:LAN:UPD`.
+    ///
+    /// Settings only take effect on update, so it is sent only when all of them
+    /// were accepted, whatever the `ErrorCheck` policy is. The update restarts
+    /// LAN interface of the device and this connection is lost, reconnect to
+    /// the new address afterwards.
+    pub fn lan_configure(&mut self, config: &LanConfig) -> crate::Result<()> {
+        config.validate().map_err(|e| Self::rejected("SYST:COMM:LAN:UPD", e))?;
+        let mut batch = self.batch();
+        for cmd in config.commands() {
+            batch.command(&cmd);
+        }
+        batch.send()?;
+        let start = Instant::now();
+        let err = self.system_error()?;
+        self.check_error(err, Self::context("SYST:ERR?", start, None))?;
+        // No error check, device drops connection on update.
+        self.send_and_disconnect("SYST:COMM:LAN:UPD")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> LanConfig {
+        LanConfig {
+            dhcp: false,
+            address: Ipv4Addr::new(10, 0, 0, 9),
+            subnet_mask: Ipv4Addr::new(255, 255, 255, 0),
+            gateway: Ipv4Addr::new(10, 0, 0, 1),
+            dns: [Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::UNSPECIFIED],
+            wins: [Ipv4Addr::UNSPECIFIED; 2],
+            hostname: String::from("A-53220A-01234"),
+            telnet_prompt: String::from("53220A> "),
+            telnet_welcome: String::from("Welcome"),
+        }
+    }
+
+    #[test]
+    fn validate() {
+        let mut c = config();
+        assert!(c.validate().is_ok());
+        c.subnet_mask = Ipv4Addr::new(255, 0, 255, 0);
+        assert!(c.validate().is_err());
+        c.subnet_mask = Ipv4Addr::UNSPECIFIED;
+        assert!(c.validate().is_err());
+        c = config();
+        c.gateway = Ipv4Addr::new(10, 0, 1, 1);
+        assert!(c.validate().is_err());
+        c.gateway = Ipv4Addr::UNSPECIFIED;
+        assert!(c.validate().is_ok());
+        c = config();
+        c.address = Ipv4Addr::UNSPECIFIED;
+        assert!(c.validate().is_err());
+        c.dhcp = true;
+        assert!(c.validate().is_ok());
+    }
+
+    #[test]
+    fn hostname() {
+        assert!(check_hostname("A-53220A-01234").is_ok());
+        for name in ["", "-counter", "counter-", "lab_counter", "lab.counter"] {
+            let e = check_hostname(name).unwrap_err();
+            assert_eq!(e.dev().unwrap().variant(), Some(KsDevErrVariant::InvalidString));
+        }
+        let e = check_hostname(&"A".repeat(HOSTNAME_MAX_LEN + 1)).unwrap_err();
+        assert_eq!(e.dev().unwrap().code(), -144);
+    }
+
+    #[test]
+    fn commands() {
+        let cmds = config().commands();
+        assert_eq!(cmds[1], "SYST:COMM:LAN:IPAD \"10.0.0.9\"");
+        assert!(cmds.contains(&String::from("SYST:COMM:LAN:DNS1 \"10.0.0.1\"")));
+        assert!(cmds.contains(&String::from("SYST:COMM:LAN:DNS2 \"0.0.0.0\"")));
+        assert!(cmds.contains(&String::from("SYST:COMM:LAN:WINS2 \"0.0.0.0\"")));
+        assert!(!cmds.iter().any(|c| c.contains("UPD")));
+    }
+}
@@ -0,0 +1,48 @@
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use crate::{KsFc};
+use crate::types::{Identity};
+
+
+/// Complete instrument setup captured with `*LRN?`.
+///
+/// The setup string is opaque and only meaningful for the model it was
+/// learned from.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LearnedState {
+    identity: Identity,
+    setup: String,
+}
+
+impl LearnedState {
+    /// Instrument the state was learned from.
+    pub fn identity(&self) -> &Identity {
+        &self.identity
+    }
+}
+
+impl KsFc {
+    /// `*LRN?`
+    pub fn learn(&mut self) -> crate::Result<LearnedState> {
+        let identity = self.identity()?;
+        let setup = self.idempotent(|fc| fc.query("*LRN?", |text| Ok(String::from(text.trim()))))?;
+        Ok(LearnedState { identity, setup })
+    }
+
+    /// Replays learned setup.
+    ///
+    /// Fails without touching the device if it is a different model.
+    pub fn restore(&mut self, state: &LearnedState) -> crate::Result<()> {
+        let identity = self.identity()?;
+        if identity.model != state.identity.model {
+            let err = format!(
+                "State learned from {} can't be restored on {}",
+                state.identity.model, identity.model,
+            ).into();
+            return Err(Self::rejected(&state.setup, err));
+        }
+        self.command(&state.setup)
+    }
+}
@@ -6,9 +6,29 @@ pub mod format;
 pub mod constants;
 pub mod types;
 pub mod api;
+pub mod retry;
+pub mod check;
+pub mod builder;
+pub mod batch;
+pub mod setup;
+pub mod stream;
+pub mod learn;
+pub mod mmem;
+pub mod lock;
+pub mod lan;
 
-pub use error::{Error, Result};
-pub use deverr::{KsDevErr};
+pub use error::{Error, Result, Context};
+pub use deverr::{KsDevErr, KsDevErrKind, KsDevErrVariant};
+pub use retry::{RetryPolicy};
+pub use check::{ErrorCheck};
+pub use builder::{KsFcBuilder, Transport};
+pub use batch::{Batch, BatchReply, Slot};
+pub use setup::{CounterSetup, GateOutput, Difference};
+pub use stream::{Stream};
+pub use learn::{LearnedState};
+pub use mmem::{Mmem, Catalog, DirEntry, FileKind};
+pub use lock::{LockGuard};
+pub use lan::{LanConfig};
 
 
 #[macro_use]
@@ -16,21 +36,53 @@ extern crate lazy_static;
 #[macro_use]
 extern crate bitflags;
 
-use std::time::{Duration};
+use std::io;
+use std::time::{Duration, Instant};
+use std::thread::{sleep};
 
 use ks_lxi::{KsDevice, KsData};
 
+use format::{into_text, into_bin};
+use constants::*;
+
 
 pub struct KsFc {
     lxi: KsDevice,
+    read_timeout: Option<Duration>,
+    check: ErrorCheck,
+    retry: Option<RetryPolicy>,
 }
 
 impl KsFc {
     pub fn new(host: &str, port: Option<u16>, timeout: Duration) -> Self {
-        Self { lxi: KsDevice::new((
-            String::from(host),
-            port.unwrap_or(5025),
-        ), Some(timeout)) }
+        Self {
+            lxi: KsDevice::new((
+                String::from(host),
+                port.unwrap_or(SOCKET_PORT),
+            ), Some(timeout)),
+            read_timeout: None,
+            check: ErrorCheck::default(),
+            retry: None,
+        }
+    }
+
+    pub fn builder(host: &str) -> KsFcBuilder {
+        KsFcBuilder::new(host)
+    }
+
+    pub fn error_check(&self) -> ErrorCheck {
+        self.check
+    }
+    pub fn set_error_check(&mut self, check: ErrorCheck) {
+        self.check = check;
+    }
+
+    pub fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.retry.as_ref()
+    }
+    /// Enables reconnection and retrying of idempotent queries.
+    pub fn set_retry_policy(&mut self, policy: Option<RetryPolicy>) {
+        self.retry = policy;
     }
 
     pub fn connect(&mut self) -> crate::Result<()> {
@@ -38,23 +90,211 @@ impl KsFc {
         self.lxi.connect().map_err(|e| e.into())
     }
     pub fn disconnect(&mut self) {
-        match self.lxi.disconnect() { _ => () }
+        let _ = self.lxi.disconnect();
     }
     pub fn is_connected(&mut self) -> bool {
         self.lxi.is_connected()
     }
 
+    /// Sends command not covered by the API, errors are checked according to `ErrorCheck` policy.
+    pub fn send_command(&mut self, cmd: &str) -> crate::Result<()> {
+        self.command(cmd)
+    }
+
+    /// Converts transport error dropping the connection if it is lost.
+    fn transport_error(&mut self, e: io::Error) -> crate::Error {
+        let err = crate::Error::from(e);
+        if let crate::Error::Disconnected = err {
+            self.disconnect();
+        }
+        err
+    }
+
+    /// Reconnects if connection is lost and retry policy is set.
+    fn ensure_connected(&mut self) -> crate::Result<()> {
+        if self.is_connected() {
+            Ok(())
+        } else if self.retry.is_some() {
+            self.connect()
+        } else {
+            Err(crate::Error::NotConnected)
+        }
+    }
+
     fn send(&mut self, data: &[u8]) -> crate::Result<()> {
-        self.lxi.send(data).map_err(|e| e.into())
+        self.ensure_connected()?;
+        self.lxi.send(data).map_err(|e| self.transport_error(e))
     }
     fn receive(&mut self) -> crate::Result<KsData> {
-        self.lxi.receive().map_err(|e| e.into())
+        match self.read_timeout {
+            Some(to) => self.lxi.receive_timeout(Some(to)),
+            None => self.lxi.receive(),
+        }
+        .map_err(|e| self.transport_error(e))
     }
-    #[allow(dead_code)]
     fn send_timeout(&mut self, data: &[u8], to: Duration) -> crate::Result<()> {
-        self.lxi.send_timeout(data, Some(to)).map_err(|e| e.into())
+        self.ensure_connected()?;
+        self.lxi.send_timeout(data, Some(to)).map_err(|e| self.transport_error(e))
     }
     fn receive_timeout(&mut self, to: Duration) -> crate::Result<KsData> {
-        self.lxi.receive_timeout(Some(to)).map_err(|e| e.into())
+        self.lxi.receive_timeout(Some(to)).map_err(|e| self.transport_error(e))
+    }
+}
+
+// Command exchange, all errors are reported with command context
+impl KsFc {
+    /// Marks exchange `f` as idempotent, so it is repeated on connection failures
+    /// according to retry policy.
+    fn idempotent<T, F>(&mut self, mut f: F) -> crate::Result<T>
+    where F: FnMut(&mut Self) -> crate::Result<T> {
+        let policy = match &self.retry {
+            Some(p) => p.clone(),
+            None => return f(self),
+        };
+        let mut delay = policy.backoff;
+        let mut attempt = 0;
+        loop {
+            // Reconnection is done by `send`, so its errors get command context.
+            let res = f(self);
+            let retry = match &res {
+                Err(e) => e.is_retryable() || !self.is_connected(),
+                Ok(_) => false,
+            };
+            match res {
+                Err(_) if retry && attempt < policy.retries => {
+                    // Late reply to a timed out query must not be taken
+                    // for the reply to the repeated one.
+                    self.disconnect();
+                    sleep(delay);
+                    delay *= policy.factor;
+                    attempt += 1;
+                },
+                res => break res,
+            }
+        }
+    }
+
+    fn context(cmd: &str, start: Instant, response: Option<&[u8]>) -> Context {
+        Context {
+            command: String::from(cmd),
+            response: response.map(|r| r.to_vec()),
+            elapsed: start.elapsed(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Error found before `cmd` is sent, reported with its context like device errors.
+    fn rejected(cmd: &str, err: crate::Error) -> crate::Error {
+        err.with_context(Self::context(cmd, Instant::now(), None))
+    }
+
+    /// Reports the first of errors and drains the rest of error queue,
+    /// so they are not attributed to the following commands.
+    ///
+    /// The drained errors are kept in `ctx.errors`.
+    /// Lock conflict is reported as `Error::Locked` with the current owner.
+    fn check_error(&mut self, err: Option<KsDevErr>, ctx: Context) -> crate::Result<()> {
+        match err {
+            Some(e) => {
+                let errors = self.system_errors()?;
+                let err = if e.variant() == Some(KsDevErrVariant::Locked) {
+                    crate::Error::Locked { owner: self.system_lock_owner()? }
+                } else {
+                    e.into()
+                };
+                Err(err.with_context(Context { errors, ..ctx }))
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// Sends command that has no reply and checks errors according to `ErrorCheck` policy.
+    fn command(&mut self, cmd: &str) -> crate::Result<()> {
+        self.command_data(cmd, None, None)
+    }
+
+    /// Sends command with optional binary block argument.
+    ///
+    /// `timeout` applies to sending and to the error check.
+    /// Only the header is reported in error context.
+    fn command_data(&mut self, cmd: &str, block: Option<&[u8]>, timeout: Option<Duration>) -> crate::Result<()> {
+        let start = Instant::now();
+        let mut msg = cmd.as_bytes().to_vec();
+        if let Some(data) = block {
+            msg.push(b' ');
+            msg.extend(format::bin_block(data));
+        }
+        let send = |fc: &mut Self, data: &[u8]| match timeout {
+            Some(to) => fc.send_timeout(data, to),
+            None => fc.send(data),
+        };
+        match self.check {
+            ErrorCheck::Never => send(self, &msg),
+            ErrorCheck::AfterEach => {
+                send(self, &msg)
+                .and_then(|()| self.query_timeout("SYST:ERR?", timeout, api::system::parse_error_reply))
+                .and_then(|e| self.check_error(e, Self::context(cmd, start, None)))
+            },
+            ErrorCheck::Batched => {
+                // Separate message, device drops the rest of the one with a command error.
+                send(self, &msg)
+                .and_then(|()| self.send(b"SYST:ERR?"))
+                .and_then(|()| match timeout {
+                    Some(to) => self.receive_timeout(to),
+                    None => self.receive(),
+                })
+                .and_then(into_text)
+                .and_then(|text| api::system::parse_error_reply(&text))
+                .and_then(|e| self.check_error(e, Self::context(cmd, start, None)))
+            },
+        }
+        .map_err(|e| e.with_context(Self::context(cmd, start, None)))
+    }
+
+    /// Sends query and receives raw reply.
+    fn query_data(&mut self, cmd: &str, timeout: Option<Duration>) -> crate::Result<KsData> {
+        let start = Instant::now();
+        self.send(cmd.as_bytes())
+        .and_then(|()| match timeout {
+            Some(to) => self.receive_timeout(to),
+            None => self.receive(),
+        })
+        .map_err(|e| e.with_context(Self::context(cmd, start, None)))
+    }
+
+    /// Sends query and parses its text reply with `f`.
+    fn query_timeout<T, F>(&mut self, cmd: &str, timeout: Option<Duration>, f: F) -> crate::Result<T>
+    where F: FnOnce(&str) -> crate::Result<T> {
+        let start = Instant::now();
+        self.query_data(cmd, timeout)
+        .and_then(|data| {
+            into_text(data)
+            .map_err(|e| e.with_context(Self::context(cmd, start, None)))
+        })
+        .and_then(|text| {
+            f(&text)
+            .map_err(|e| e.with_context(Self::context(cmd, start, Some(text.as_bytes()))))
+        })
+    }
+
+    /// Sends query and parses its binary reply with `f`.
+    fn query_bin<T, F>(&mut self, cmd: &str, timeout: Option<Duration>, f: F) -> crate::Result<T>
+    where F: FnOnce(&[u8]) -> crate::Result<T> {
+        let start = Instant::now();
+        self.query_data(cmd, timeout)
+        .and_then(|data| {
+            into_bin(data)
+            .map_err(|e| e.with_context(Self::context(cmd, start, None)))
+        })
+        .and_then(|buf| {
+            f(&buf)
+            .map_err(|e| e.with_context(Self::context(cmd, start, Some(&buf))))
+        })
+    }
+
+    /// Sends query and parses its text reply with `f`.
+    fn query<T, F>(&mut self, cmd: &str, f: F) -> crate::Result<T>
+    where F: FnOnce(&str) -> crate::Result<T> {
+        self.query_timeout(cmd, None, f)
     }
 }
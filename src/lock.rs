@@ -0,0 +1,56 @@
+use std::ops::{Deref, DerefMut};
+use std::time::{Duration, Instant};
+use std::thread::{sleep};
+
+use crate::{KsFc};
+use crate::constants::*;
+
+
+/// Exclusive access to the device, released on drop.
+///
+/// Derefs to `KsFc`, so the device is used through the guard while locked.
+pub struct LockGuard<'a> {
+    fc: &'a mut KsFc,
+}
+
+impl<'a> Deref for LockGuard<'a> {
+    type Target = KsFc;
+    fn deref(&self) -> &KsFc {
+        self.fc
+    }
+}
+
+impl<'a> DerefMut for LockGuard<'a> {
+    fn deref_mut(&mut self) -> &mut KsFc {
+        self.fc
+    }
+}
+
+impl<'a> Drop for LockGuard<'a> {
+    fn drop(&mut self) {
+        // Lock is also released by device when the session is closed.
+        let _ = self.fc.system_lock_release();
+    }
+}
+
+impl KsFc {
+    /// Locks the device for this session with `SYST:LOCK:REQ?`.
+    ///
+    /// The request is repeated every `LOCK_POLL_PERIOD` until `timeout`
+    /// expires, then `Error::Locked` with the current owner is returned.
+    pub fn lock(&mut self, timeout: Duration) -> crate::Result<LockGuard<'_>> {
+        let start = Instant::now();
+        loop {
+            if self.system_lock_request()? {
+                break Ok(LockGuard { fc: self });
+            }
+            let elapsed = start.elapsed();
+            if elapsed >= timeout {
+                let owner = self.system_lock_owner()?;
+                let err = crate::Error::Locked { owner };
+                break Err(err.with_context(Self::context("SYST:LOCK:REQ?", start, None)));
+            }
+            sleep(LOCK_POLL_PERIOD.min(timeout - elapsed));
+        }
+    }
+}
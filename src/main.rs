@@ -1,11 +1,14 @@
 //#![allow(dead_code)]
 
+use std::convert::{TryFrom};
 use std::time::{Duration};
 use std::thread::{sleep};
 
 use ksfc_lxi::{
-    KsFc, Error,
-    types::{EventReg, ChannelNo, TriggerSource},
+    KsFc, Error, RetryPolicy, ErrorCheck, CounterSetup, GateOutput,
+    KsDevErrVariant, deverr::{ConflictKind, FileNameKind},
+    FileKind,
+    types::{EventReg, StatusByte, OperationReg, QuestionableReg, ChannelNo, TriggerSource, Slope, Polarity, GateSource, StateSlot, ImageFormat, DisplayMode, Interface},
 };
 
 
@@ -47,27 +50,50 @@ macro_rules! test_all {
 fn main() {
     test_all!(
         {
-            let (dev, res) = KsFc::new(
-                &"10.0.0.9", None,
-                Duration::from_secs(2),
-            );
-            res.unwrap();
-            dev
+            KsFc::builder("10.0.0.9")
+            .connect_timeout(Duration::from_secs(2))
+            .build()
+            .unwrap()
         },
         [
             test_abort,
             test_cls,
+            test_errors,
             //test_cal,
             test_ese,
+            test_sre,
+            test_wait_event,
+            test_status_regs,
             test_autoscale,
             test_init,
             test_fetch,
             test_read,
             test_trig_count,
             test_trig_delay,
+            test_error_check,
+            test_batch,
             test_r,
             test_conf_freq,
             test_trig_source,
+            test_trig_slope,
+            test_software_trigger,
+            test_gate_output,
+            test_state_memory,
+            test_learn,
+            test_mmem,
+            test_file_transfer,
+            test_screenshot,
+            test_display,
+            test_lock,
+            test_lan_config,
+            test_communicate,
+            test_lxi,
+            test_lxi_restart,
+            test_retry,
+            test_setup,
+            test_verify_setup,
+            test_sample_count,
+            test_stream,
         ]
     );
 }
@@ -81,6 +107,11 @@ fn test_cls(fc: &mut KsFc) {
     fc.cls().unwrap();
 }
 
+fn test_errors(fc: &mut KsFc) {
+    fc.cls().unwrap();
+    assert!(fc.system_errors().unwrap().is_empty());
+}
+
 #[allow(dead_code)]
 fn test_cal(fc: &mut KsFc) {
     assert!(fc.cal().unwrap());
@@ -91,13 +122,39 @@ fn test_ese(fc: &mut KsFc) {
     assert_eq!(fc.ese_get().unwrap(), EventReg::all());
 }
 
+fn test_sre(fc: &mut KsFc) {
+    fc.sre_set(StatusByte::EVENT_SUMMARY).unwrap();
+    assert_eq!(fc.sre_get().unwrap(), StatusByte::EVENT_SUMMARY);
+}
+
+fn test_wait_event(fc: &mut KsFc) {
+    fc.cls().unwrap();
+    fc.ese_set(EventReg::OP_COMPLETE).unwrap();
+    fc.initiate().unwrap();
+    fc.opc().unwrap();
+    fc.wait_event(StatusByte::EVENT_SUMMARY, 2*MEAS_TIME).unwrap();
+    assert!(fc.esr_get().unwrap().contains(EventReg::OP_COMPLETE));
+}
+
+fn test_status_regs(fc: &mut KsFc) {
+    fc.status_operation_enable_set(OperationReg::MEASURING).unwrap();
+    assert_eq!(fc.status_operation_enable_get().unwrap(), OperationReg::MEASURING);
+    fc.status_questionable_enable_set(QuestionableReg::LIMIT_FAIL).unwrap();
+    assert_eq!(fc.status_questionable_enable_get().unwrap(), QuestionableReg::LIMIT_FAIL);
+
+    fc.status_preset().unwrap();
+    assert_eq!(fc.status_operation_enable_get().unwrap(), OperationReg::empty());
+    fc.status_operation_event_get().unwrap();
+    fc.status_questionable_condition_get().unwrap();
+}
+
 fn test_autoscale(fc: &mut KsFc) {
     fc.autoscale().unwrap();
 }
 
 fn test_init(fc: &mut KsFc) {
     fc.initiate().unwrap();
-    if let Err(Error::Dev(x)) = fc.initiate() {
+    if let Err(Error::Dev(x)) = fc.initiate().map_err(Error::into_root) {
         assert_eq!(x.code(), -213);
     }
     sleep(MEAS_TIME);
@@ -119,10 +176,10 @@ fn test_trig_count(fc: &mut KsFc) {
     fc.trigger_count_set(1000000).unwrap();
     assert_eq!(fc.trigger_count_get().unwrap(), 1000000);
 
-    if let Err(Error::Dev(x)) = fc.trigger_count_set(0) {
+    if let Err(Error::Dev(x)) = fc.trigger_count_set(0).map_err(Error::into_root) {
         assert_eq!(x.code(), -222);
     }
-    if let Err(Error::Dev(x)) = fc.trigger_count_set(1000001) {
+    if let Err(Error::Dev(x)) = fc.trigger_count_set(1000001).map_err(Error::into_root) {
         assert_eq!(x.code(), -222);
     }
 }
@@ -134,11 +191,55 @@ fn test_trig_delay(fc: &mut KsFc) {
     fc.trigger_delay_set(Duration::from_micros(10)).unwrap();
     assert_eq!(fc.trigger_delay_get().unwrap().as_micros(), 10);
 
-    if let Err(Error::Dev(x)) = fc.trigger_delay_set(Duration::from_secs(3601)) {
+    if let Err(Error::Dev(x)) = fc.trigger_delay_set(Duration::from_secs(3601)).map_err(Error::into_root) {
         assert_eq!(x.code(), -222);
     }
 }
 
+fn test_error_check(fc: &mut KsFc) {
+    fc.set_error_check(ErrorCheck::Batched);
+    fc.trigger_count_set(2).unwrap();
+    assert_eq!(fc.trigger_count_get().unwrap(), 2);
+    assert_eq!(fc.trigger_count_set(0).unwrap_err().dev().unwrap().code(), -222);
+    // Command error makes device drop the rest of the message
+    assert_eq!(fc.send_command("TRIG:BOGUS 1").unwrap_err().dev().unwrap().code(), -113);
+    assert_eq!(fc.trigger_count_get().unwrap(), 2);
+
+    fc.set_error_check(ErrorCheck::Never);
+    fc.trigger_count_set(0).unwrap();
+    assert_eq!(fc.system_errors().unwrap().len(), 1);
+
+    // Errors queued after the reported one are kept in context
+    fc.trigger_count_set(0).unwrap();
+    fc.set_error_check(ErrorCheck::AfterEach);
+    let err = fc.trigger_count_set(0).unwrap_err();
+    assert_eq!(err.dev().unwrap().code(), -222);
+    assert_eq!(err.context().unwrap().errors.len(), 1);
+    assert!(fc.system_errors().unwrap().is_empty());
+}
+
+fn test_batch(fc: &mut KsFc) {
+    let mut batch = fc.batch();
+    batch.trigger_count_set(3).trigger_delay_set(Duration::from_millis(10));
+    let count = batch.trigger_count_get();
+    let delay = batch.trigger_delay_get();
+    let reply = batch.send().unwrap();
+    assert_eq!(reply.get(count).unwrap(), 3);
+    assert_eq!(reply.get(delay).unwrap().as_millis(), 10);
+
+    let mut batch = fc.batch();
+    batch.trigger_count_set(1).trigger_count_set(0);
+    assert_eq!(batch.send().unwrap_err().dev().unwrap().code(), -222);
+
+    // Command error drops the rest of the batch, queries get no reply
+    let mut batch = fc.batch();
+    batch.command("*CLS").trigger_count_set(2).command("TRIG:BOGUS 1");
+    batch.trigger_count_get();
+    let err = batch.send().unwrap_err();
+    assert_eq!(err.dev().unwrap().code(), -113);
+    assert_eq!(fc.trigger_count_get().unwrap(), 2);
+}
+
 fn test_r(fc: &mut KsFc) {
     fc.trigger_count_set(4).unwrap();
     fc.initiate().unwrap();
@@ -162,7 +263,272 @@ fn test_conf_freq(fc: &mut KsFc) {
 
 
 fn test_trig_source(fc: &mut KsFc) {
-    fc.trigger_source_set(TriggerSource::Immediate).unwrap();
+    for source in [TriggerSource::External, TriggerSource::Bus, TriggerSource::Immediate] {
+        fc.trigger_source_set(source).unwrap();
+        assert_eq!(fc.trigger_source_get().unwrap(), source);
+    }
+    fc.initiate().unwrap();
+    assert_freq(fc.fetch().unwrap());
+}
+
+fn test_trig_slope(fc: &mut KsFc) {
+    assert_eq!(fc.trigger_slope_get().unwrap(), Slope::Negative);
+    fc.trigger_slope_set(Slope::Positive).unwrap();
+    assert_eq!(fc.trigger_slope_get().unwrap(), Slope::Positive);
+}
+
+fn test_software_trigger(fc: &mut KsFc) {
+    let e = fc.software_trigger().unwrap_err();
+    assert_eq!(
+        e.dev().unwrap().variant(),
+        Some(KsDevErrVariant::SettingsConflict(ConflictKind::TriggerNotBus)),
+    );
+
+    fc.trigger_source_set(TriggerSource::Bus).unwrap();
     fc.initiate().unwrap();
+    fc.software_trigger().unwrap();
     assert_freq(fc.fetch().unwrap());
 }
+
+fn test_gate_output(fc: &mut KsFc) {
+    assert_eq!(fc.gate_output_get().unwrap(), GateOutput::default());
+    let output = GateOutput { enabled: true, polarity: Polarity::Inverted };
+    fc.gate_output_set(&output).unwrap();
+    assert_eq!(fc.gate_output_get().unwrap(), output);
+
+    fc.output_state_set(false).unwrap();
+    fc.sense_frequency_gate_source_set(GateSource::External).unwrap();
+    let e = fc.output_state_set(true).unwrap_err();
+    assert_eq!(
+        e.dev().unwrap().variant(),
+        Some(KsDevErrVariant::SettingsConflict(ConflictKind::ExtGateOutput)),
+    );
+}
+
+fn test_state_memory(fc: &mut KsFc) {
+    assert_eq!(fc.memory_nstates().unwrap(), StateSlot::COUNT as usize);
+    assert!(StateSlot::try_from(StateSlot::COUNT).is_err());
+    let slot = StateSlot::try_from(1).unwrap();
+
+    fc.trigger_count_set(3).unwrap();
+    fc.sav(slot).unwrap();
+    assert!(fc.memory_state_valid(slot).unwrap());
+    fc.rst().unwrap();
+    fc.rcl(slot).unwrap();
+    assert_eq!(fc.trigger_count_get().unwrap(), 3);
+
+    fc.memory_state_recall_select_set(slot).unwrap();
+    fc.memory_state_recall_auto_set(true).unwrap();
+    let e = fc.memory_state_delete(slot).unwrap_err();
+    assert_eq!(
+        e.dev().unwrap().variant(),
+        Some(KsDevErrVariant::SettingsConflict(ConflictKind::DeleteAutoRecallState)),
+    );
+
+    fc.memory_state_recall_auto_set(false).unwrap();
+    fc.memory_state_delete(slot).unwrap();
+    assert!(!fc.memory_state_valid(slot).unwrap());
+}
+
+fn test_learn(fc: &mut KsFc) {
+    fc.trigger_count_set(5).unwrap();
+    let state = fc.learn().unwrap();
+    assert_eq!(state.identity(), &fc.identity().unwrap());
+
+    fc.rst().unwrap();
+    fc.restore(&state).unwrap();
+    assert_eq!(fc.trigger_count_get().unwrap(), 5);
+}
+
+fn test_mmem(fc: &mut KsFc) {
+    let mut mmem = fc.mmem();
+    mmem.make_dir("INT:\\ksfc_test").unwrap();
+    let e = mmem.make_dir("INT:\\ksfc_test").unwrap_err();
+    assert_eq!(
+        e.dev().unwrap().variant(),
+        Some(KsDevErrVariant::FileName(FileNameKind::AlreadyExists)),
+    );
+
+    mmem.store_state("INT:\\ksfc_test\\a.sta").unwrap();
+    mmem.copy("INT:\\ksfc_test\\a.sta", "INT:\\ksfc_test\\b.sta").unwrap();
+    let cat = mmem.catalog(Some("INT:\\ksfc_test")).unwrap();
+    assert_eq!(cat.entries.len(), 2);
+    assert!(cat.entries.iter().all(|e| e.kind == FileKind::State && e.size > 0));
+
+    mmem.load_state("INT:\\ksfc_test\\b.sta").unwrap();
+    mmem.delete("INT:\\ksfc_test\\a.sta").unwrap();
+    mmem.delete("INT:\\ksfc_test\\b.sta").unwrap();
+    mmem.remove_dir("INT:\\ksfc_test").unwrap();
+}
+
+fn test_file_transfer(fc: &mut KsFc) {
+    let path = "INT:\\ksfc_test.sta";
+    fc.mmem().store_state(path).unwrap();
+    let data = fc.upload_file(path).unwrap();
+    assert!(!data.is_empty());
+
+    fc.mmem().delete(path).unwrap();
+    fc.download_file(path, &data).unwrap();
+    assert_eq!(fc.upload_file(path).unwrap(), data);
+    fc.mmem().load_state(path).unwrap();
+    fc.mmem().delete(path).unwrap();
+
+    // Payload that looks like a block header is kept as is
+    let path = "INT:\\ksfc_test.csv";
+    fc.download_file(path, b"#13abc,1\n").unwrap();
+    assert_eq!(fc.upload_file(path).unwrap(), b"#13abc,1\n");
+
+    // Larger file arrives whole, replacing the previous one
+    let data: Vec<u8> = (0..(3*64*1024 + 123)).map(|i| b"0123456789,\n"[i % 12]).collect();
+    fc.download_file(path, &data).unwrap();
+    assert_eq!(fc.upload_file(path).unwrap(), data);
+    fc.mmem().delete(path).unwrap();
+}
+
+fn test_screenshot(fc: &mut KsFc) {
+    for format in [ImageFormat::Png, ImageFormat::Bmp] {
+        let image = fc.screenshot(format).unwrap();
+        assert!(image.len() > format.signature().len());
+        assert_eq!(fc.hcopy_sdump_data_format_get().unwrap(), format);
+    }
+}
+
+fn test_display(fc: &mut KsFc) {
+    fc.display_text_set("RUN 42 IN PROGRESS \u{2014} DO NOT TOUCH").unwrap();
+    assert_eq!(fc.display_text_get().unwrap(), "RUN 42 IN PROGRESS - DO NOT TOUCH");
+    fc.display_text_clear().unwrap();
+    assert_eq!(fc.display_text_get().unwrap(), "");
+
+    fc.display_mode_set(DisplayMode::Histogram).unwrap();
+    assert_eq!(fc.display_mode_get().unwrap(), DisplayMode::Histogram);
+
+    fc.display_digit_mask_auto_set(false).unwrap();
+    fc.display_digit_mask_set(6).unwrap();
+    assert!(fc.display_digit_mask_set(2).is_err());
+    assert!(fc.display_digit_mask_set(16).is_err());
+    assert_eq!(fc.display_digit_mask_get().unwrap(), 6);
+    assert!(!fc.display_digit_mask_auto_get().unwrap());
+
+    fc.display_state_set(false).unwrap();
+    assert!(!fc.display_state_get().unwrap());
+    fc.display_state_set(true).unwrap();
+}
+
+fn test_lock(fc: &mut KsFc) {
+    let name = fc.system_lock_name().unwrap();
+    {
+        let mut guard = fc.lock(Duration::from_secs(1)).unwrap();
+        assert_eq!(guard.system_lock_owner().unwrap(), name);
+        guard.trigger_count_set(2).unwrap();
+    }
+    assert_eq!(fc.system_lock_owner().unwrap(), "NONE");
+
+    let mut other = KsFc::builder("10.0.0.9").build().unwrap();
+    let _guard = other.lock(Duration::from_secs(1)).unwrap();
+    match fc.lock(Duration::from_millis(300)).map_err(Error::into_root) {
+        Err(Error::Locked { owner }) => assert_ne!(owner, name),
+        _ => panic!("lock must be refused"),
+    }
+    match fc.trigger_count_set(3).map_err(Error::into_root) {
+        Err(Error::Locked { .. }) => (),
+        r => panic!("Locked error expected, got {:?}", r),
+    }
+}
+
+fn test_lan_config(fc: &mut KsFc) {
+    let config = fc.lan_config().unwrap();
+    assert!(config.validate().is_ok());
+    assert!(!config.hostname.is_empty());
+    assert!(!fc.system_communicate_lan_mac().unwrap().is_empty());
+}
+
+fn test_communicate(fc: &mut KsFc) {
+    assert!(fc.system_communicate_enable_get(Interface::Sockets).unwrap());
+    let addr = fc.system_communicate_gpib_address_get().unwrap();
+    assert!(fc.system_communicate_gpib_address_set(31).is_err());
+    fc.system_communicate_gpib_address_set(addr).unwrap();
+}
+
+fn test_lxi(fc: &mut KsFc) {
+    fc.identify_blink(Duration::from_millis(500)).unwrap();
+    assert!(!fc.lxi_identify_get().unwrap());
+
+    if fc.lxi_mdns_enable_get().unwrap() {
+        assert!(!fc.lxi_mdns_hostname_resolved().unwrap().is_empty());
+        let name = fc.lxi_mdns_service_name_desired_get().unwrap();
+        assert!(fc.lxi_mdns_service_name_resolved().unwrap().starts_with(&name));
+    }
+}
+
+fn test_lxi_restart(fc: &mut KsFc) {
+    fc.lxi_restart(Some(Duration::from_secs(60))).unwrap();
+    assert!(fc.is_connected());
+    fc.idn().unwrap();
+}
+
+fn test_retry(fc: &mut KsFc) {
+    fc.disconnect();
+    if let Err(e) = fc.trigger_count_get() {
+        assert!(e.is_retryable());
+    }
+    fc.set_retry_policy(Some(RetryPolicy::default()));
+    fc.disconnect();
+    assert_eq!(fc.trigger_count_get().unwrap(), 1);
+    assert!(fc.is_connected());
+}
+
+fn test_setup(fc: &mut KsFc) {
+    assert_eq!(fc.read_setup().unwrap(), CounterSetup::default());
+
+    let mut setup = CounterSetup::default();
+    setup.trigger.count = 5;
+    setup.gate.time = Duration::from_millis(10);
+    setup.input_mut(ChannelNo::Ch1).level = Some(0.5);
+    fc.apply(&setup).unwrap();
+    assert_eq!(fc.read_setup().unwrap(), setup);
+}
+
+fn test_verify_setup(fc: &mut KsFc) {
+    let setup = CounterSetup::default();
+    assert!(fc.verify_setup(&setup).unwrap().is_empty());
+
+    fc.trigger_count_set(7).unwrap();
+    let diff = fc.verify_setup(&setup).unwrap();
+    assert_eq!(diff.len(), 1);
+    assert_eq!(diff[0].setting, "TRIG:COUN");
+
+    assert_eq!(fc.restore_setup(&setup).unwrap().len(), 1);
+    assert_eq!(fc.trigger_count_get().unwrap(), 1);
+    assert!(fc.restore_setup(&setup).unwrap().is_empty());
+}
+
+fn test_sample_count(fc: &mut KsFc) {
+    fc.sample_count_set(10).unwrap();
+    assert_eq!(fc.sample_count_get().unwrap(), 10);
+    fc.trigger_count_set(100000).unwrap();
+    assert_eq!(fc.reading_count().unwrap(), 1000000);
+
+    // Setters alone don't check the product, measurement start does
+    fc.trigger_count_set(1000000).unwrap();
+    assert_eq!(fc.reading_count().unwrap(), 10000000);
+    assert_eq!(fc.initiate().unwrap_err().dev().unwrap().code(), -222);
+    assert_eq!(fc.read_all(MEAS_TIME).unwrap_err().dev().unwrap().code(), -222);
+    fc.sample_count_set(1).unwrap();
+    assert_eq!(fc.reading_count().unwrap(), 1000000);
+
+    fc.reading_count_set(1000, 1000).unwrap();
+    assert_eq!(fc.reading_count().unwrap(), 1000000);
+    assert_eq!(fc.reading_count_set(1000, 1001).unwrap_err().dev().unwrap().code(), -222);
+    assert_eq!(fc.sample_count_get().unwrap(), 1000);
+}
+
+fn test_stream(fc: &mut KsFc) {
+    fc.trigger_count_set(2).unwrap();
+    fc.sample_count_set(3).unwrap();
+    fc.initiate().unwrap();
+    let r = fc.read_all(2*MEAS_TIME).unwrap();
+    assert_eq!(r.len(), 6);
+    for x in r {
+        assert_freq(x);
+    }
+}
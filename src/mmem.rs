@@ -0,0 +1,273 @@
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use crate::{KsFc, KsDevErr};
+use crate::deverr::{KsDevErrVariant, FileNameKind};
+use crate::constants::*;
+use crate::format::{ParseError, parse, parse_string, quote_string, split_unquoted};
+
+
+/// Kind of mass memory catalog entry.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FileKind {
+    Folder,
+    /// Instrument state, `.sta`
+    State,
+    /// Preferences, `.prf`
+    Preferences,
+    /// Any other type reported by device
+    Other(String),
+}
+
+impl FileKind {
+    fn from_mnemonic(text: &str) -> Self {
+        match text.trim().to_ascii_uppercase().as_str() {
+            "FOLD" => FileKind::Folder,
+            "STAT" => FileKind::State,
+            "PREF" => FileKind::Preferences,
+            other => FileKind::Other(String::from(other)),
+        }
+    }
+}
+
+/// Mass memory catalog entry.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DirEntry {
+    pub name: String,
+    /// Size in bytes, zero for folders
+    pub size: u64,
+    pub kind: FileKind,
+}
+
+/// Folder listing, `MMEMory:CATalog?` reply.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Catalog {
+    /// Bytes used on the drive
+    pub used: u64,
+    /// Bytes free on the drive
+    pub free: u64,
+    pub entries: Vec<DirEntry>,
+}
+
+fn parse_entry(text: &str) -> Result<DirEntry, ParseError> {
+    let text = parse_string(text)?;
+    // Name may contain commas, so split type and size from the end.
+    let mut parts = text.rsplitn(3, ',');
+    let size = parts.next().ok_or(ParseError::EndOfString)?;
+    let kind = parts.next().ok_or(ParseError::EndOfString)?;
+    let name = parts.next().ok_or(ParseError::EndOfString)?;
+    Ok(DirEntry {
+        name: String::from(name),
+        size: parse::<u64>(size.trim())?,
+        kind: FileKind::from_mnemonic(kind),
+    })
+}
+
+fn parse_catalog(text: &str) -> Result<Catalog, ParseError> {
+    let mut parts = split_unquoted(text.trim(), ',').into_iter();
+    let used = parts.next().ok_or(ParseError::EndOfString)?;
+    let free = parts.next().ok_or(ParseError::EndOfString)?;
+    Ok(Catalog {
+        used: parse::<u64>(used.trim())?,
+        free: parse::<u64>(free.trim())?,
+        entries: parts.map(parse_entry).collect::<Result<_, _>>()?,
+    })
+}
+
+/// Checks file extension before sending, the device only reports it as -257.
+fn check_extension(file: &str, ext: &str) -> crate::Result<()> {
+    let lower = file.to_ascii_lowercase();
+    if lower.ends_with(ext) {
+        Ok(())
+    } else {
+        Err(crate::Error::Dev(KsDevErr::from(
+            KsDevErrVariant::FileName(FileNameKind::UnknownExtension)
+        )))
+    }
+}
+
+/// Folder and file name of absolute path, e.g. `USB:\a` and `b.sta` for `USB:\a\b.sta`.
+fn split_path(path: &str) -> Option<(&str, &str)> {
+    if !path.contains(':') {
+        return None;
+    }
+    path.rfind('\\').map(|i| {
+        // Drive root keeps its backslash
+        let folder = if path[..i].ends_with(':') { &path[..=i] } else { &path[..i] };
+        (folder, &path[i + 1..])
+    })
+}
+
+/// Mass memory subsystem, `MMEMory`.
+///
+/// Paths are absolute, e.g. `INT:\folder\file.sta` for internal flash
+/// or `USB:\file.sta` for USB storage. Failures with files are reported
+/// as -257 device errors, see `FileNameKind`.
+pub struct Mmem<'a> {
+    fc: &'a mut KsFc,
+}
+
+impl<'a> Mmem<'a> {
+    fn catalog_query(&mut self, cmd: &str, folder: Option<&str>) -> crate::Result<Catalog> {
+        let cmd = match folder {
+            Some(f) => format!("{} {}", cmd, quote_string(f)),
+            None => String::from(cmd),
+        };
+        self.fc.idempotent(|fc| fc.query(&cmd, |text| parse_catalog(text).map_err(|e| e.into())))
+    }
+
+    /// `MMEMory:CATalog:ALL? [<folder>]`, current folder if `None`
+    pub fn catalog(&mut self, folder: Option<&str>) -> crate::Result<Catalog> {
+        self.catalog_query("MMEM:CAT:ALL?", folder)
+    }
+
+    /// `MMEMory:CATalog:DATA? [<folder>]`, data files only
+    pub fn catalog_data(&mut self, folder: Option<&str>) -> crate::Result<Catalog> {
+        self.catalog_query("MMEM:CAT:DATA?", folder)
+    }
+
+    /// `MMEMory:CATalog:STATe? [<folder>]`, state and preferences files only
+    pub fn catalog_state(&mut self, folder: Option<&str>) -> crate::Result<Catalog> {
+        self.catalog_query("MMEM:CAT:STAT?", folder)
+    }
+
+    /// `MMEMory:CDIRectory <folder>`
+    pub fn change_dir(&mut self, folder: &str) -> crate::Result<()> {
+        self.fc.command(&format!("MMEM:CDIR {}", quote_string(folder)))
+    }
+    /// `MMEMory:CDIRectory?`
+    pub fn current_dir(&mut self) -> crate::Result<String> {
+        self.fc.idempotent(|fc| fc.query("MMEM:CDIR?", |text| parse_string(text).map_err(|e| e.into())))
+    }
+
+    /// `MMEMory:MDIRectory <folder>`
+    pub fn make_dir(&mut self, folder: &str) -> crate::Result<()> {
+        self.fc.command(&format!("MMEM:MDIR {}", quote_string(folder)))
+    }
+
+    /// `MMEMory:RDIRectory <folder>`, the folder must be empty
+    pub fn remove_dir(&mut self, folder: &str) -> crate::Result<()> {
+        self.fc.command(&format!("MMEM:RDIR {}", quote_string(folder)))
+    }
+
+    /// `MMEMory:COPY <file>, <destination>`
+    pub fn copy(&mut self, file: &str, dest: &str) -> crate::Result<()> {
+        self.fc.command(&format!("MMEM:COPY {},{}", quote_string(file), quote_string(dest)))
+    }
+
+    /// `MMEMory:MOVE <file>, <destination>`, moves or renames file
+    pub fn rename(&mut self, file: &str, dest: &str) -> crate::Result<()> {
+        self.fc.command(&format!("MMEM:MOVE {},{}", quote_string(file), quote_string(dest)))
+    }
+
+    /// `MMEMory:DELete <file>`
+    pub fn delete(&mut self, file: &str) -> crate::Result<()> {
+        self.fc.command(&format!("MMEM:DEL {}", quote_string(file)))
+    }
+
+    /// `MMEMory:LOAD:STATe <file>`, `.sta` file
+    pub fn load_state(&mut self, file: &str) -> crate::Result<()> {
+        let cmd = format!("MMEM:LOAD:STAT {}", quote_string(file));
+        check_extension(file, ".sta").map_err(|e| KsFc::rejected(&cmd, e))?;
+        self.fc.command(&cmd)
+    }
+    /// `MMEMory:STORe:STATe <file>`, `.sta` file
+    pub fn store_state(&mut self, file: &str) -> crate::Result<()> {
+        let cmd = format!("MMEM:STOR:STAT {}", quote_string(file));
+        check_extension(file, ".sta").map_err(|e| KsFc::rejected(&cmd, e))?;
+        self.fc.command(&cmd)
+    }
+
+    /// `MMEMory:LOAD:PREFerences <file>`, `.prf` file
+    pub fn load_preferences(&mut self, file: &str) -> crate::Result<()> {
+        let cmd = format!("MMEM:LOAD:PREF {}", quote_string(file));
+        check_extension(file, ".prf").map_err(|e| KsFc::rejected(&cmd, e))?;
+        self.fc.command(&cmd)
+    }
+    /// `MMEMory:STORe:PREFerences <file>`, `.prf` file
+    pub fn store_preferences(&mut self, file: &str) -> crate::Result<()> {
+        let cmd = format!("MMEM:STOR:PREF {}", quote_string(file));
+        check_extension(file, ".prf").map_err(|e| KsFc::rejected(&cmd, e))?;
+        self.fc.command(&cmd)
+    }
+
+    /// `MMEMory:STORe:DATA RDG_STORE, <file>`, saves reading memory
+    pub fn store_data(&mut self, file: &str) -> crate::Result<()> {
+        self.fc.command(&format!("MMEM:STOR:DATA RDG_STORE,{}", quote_string(file)))
+    }
+}
+
+impl KsFc {
+    /// Mass memory file operations.
+    pub fn mmem(&mut self) -> Mmem<'_> {
+        Mmem { fc: self }
+    }
+
+    /// `MMEMory:UPLoad? <file>`, reads file from device.
+    pub fn upload_file(&mut self, path: &str) -> crate::Result<Vec<u8>> {
+        let cmd = format!("MMEM:UPL? {}", quote_string(path));
+        self.idempotent(|fc| fc.query_bin(&cmd, Some(FILE_TIMEOUT), |data| Ok(data.to_vec())))
+    }
+
+    /// Writes file to device with `MMEMory:DOWNload:FNAMe <file>` and
+    /// `MMEMory:DOWNload:DATA <block>`.
+    ///
+    /// Device replaces file contents with each `MMEM:DOWN:DATA` block, so
+    /// the file can't be sent in chunks and goes as a single block with
+    /// `FILE_TIMEOUT`. Free space on the drive, plus the size of the file
+    /// being replaced, is checked first, so a file that doesn't fit fails
+    /// with -254 "Media full" before any data is written.
+    pub fn download_file(&mut self, path: &str, data: &[u8]) -> crate::Result<()> {
+        if let Some((folder, name)) = split_path(path) {
+            let catalog = self.mmem().catalog(Some(folder))?;
+            let replaced = catalog.entries.iter()
+            .find(|e| e.kind != FileKind::Folder && e.name.eq_ignore_ascii_case(name))
+            .map_or(0, |e| e.size);
+            if data.len() as u64 > catalog.free + replaced {
+                let err = crate::Error::Dev(KsDevErr::from(KsDevErrVariant::MediaFull));
+                return Err(Self::rejected("MMEM:DOWN:DATA", err));
+            }
+        }
+        self.command(&format!("MMEM:DOWN:FNAM {}", quote_string(path)))?;
+        self.command_data("MMEM:DOWN:DATA", Some(data), Some(FILE_TIMEOUT))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalog() {
+        let text = "+4096,+1000000,\"state1.sta,STAT,1536\",\"data, old,FOLD,0\",\"log.csv,ASC,2560\"\n";
+        let cat = parse_catalog(text).unwrap();
+        assert_eq!((cat.used, cat.free), (4096, 1000000));
+        assert_eq!(cat.entries, vec![
+            DirEntry { name: String::from("state1.sta"), size: 1536, kind: FileKind::State },
+            DirEntry { name: String::from("data, old"), size: 0, kind: FileKind::Folder },
+            DirEntry { name: String::from("log.csv"), size: 2560, kind: FileKind::Other(String::from("ASC")) },
+        ]);
+        assert!(parse_catalog("+0,+10").unwrap().entries.is_empty());
+    }
+
+    #[test]
+    fn drive() {
+        assert_eq!(split_path("USB:\\a\\b.sta").unwrap(), ("USB:\\a", "b.sta"));
+        assert_eq!(split_path("USB:\\b.sta").unwrap(), ("USB:\\", "b.sta"));
+        assert_eq!(split_path("b.sta"), None);
+    }
+
+    #[test]
+    fn extension() {
+        assert!(check_extension("INT:\\a\\STATE.STA", ".sta").is_ok());
+        let e = check_extension("INT:\\a\\state.txt", ".sta").unwrap_err();
+        assert_eq!(
+            e.dev().unwrap().variant(),
+            Some(KsDevErrVariant::FileName(FileNameKind::UnknownExtension)),
+        );
+    }
+}
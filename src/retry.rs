@@ -0,0 +1,38 @@
+use std::time::{Duration};
+
+
+/// Reconnection and retry policy for idempotent queries.
+///
+/// Non-idempotent commands (`INIT`, `*TRG`, `R?`, `SYST:ERR?`, ...) are never repeated,
+/// their failures are always reported to the caller.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Number of attempts after the first failed one
+    pub retries: usize,
+    /// Delay before the first retry
+    pub backoff: Duration,
+    /// Backoff multiplier applied after each retry
+    pub factor: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            backoff: Duration::from_millis(200),
+            factor: 2,
+        }
+    }
+}
+
+impl crate::Error {
+    /// Whether the failed exchange is worth repeating after reconnection.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.root(),
+            crate::Error::Timeout { .. } |
+            crate::Error::Disconnected |
+            crate::Error::NotConnected
+        )
+    }
+}
@@ -0,0 +1,561 @@
+use std::fmt;
+use std::time::{Duration};
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use crate::{KsFc, KsDevErr, Slot};
+use crate::deverr::{KsDevErrVariant, ConflictKind};
+use crate::api::sample::{check_readings};
+use crate::types::*;
+use crate::format::{parse_flag, parse_float, parse_secs, parse_mnemonic, bool_as_str, dur_as_secs};
+
+
+/// Input channel settings, `INPut{1|2}` subsystem.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InputSetup {
+    pub coupling: Coupling,
+    pub impedance: Impedance,
+    /// Input range, V
+    pub range: f64,
+    /// Threshold level, V, `None` for auto-level
+    pub level: Option<f64>,
+    pub slope: Slope,
+    /// Low-pass filter
+    pub filter: bool,
+    /// Noise rejection
+    pub noise_reject: bool,
+}
+
+impl Default for InputSetup {
+    fn default() -> Self {
+        Self {
+            coupling: Coupling::Ac,
+            impedance: Impedance::High,
+            range: 5.0,
+            level: None,
+            slope: Slope::Positive,
+            filter: false,
+            noise_reject: false,
+        }
+    }
+}
+
+/// Gate output signal, `OUTPut` subsystem.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GateOutput {
+    /// Not compatible with `GateSource::External`
+    pub enabled: bool,
+    pub polarity: Polarity,
+}
+
+impl Default for GateOutput {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            polarity: Polarity::Normal,
+        }
+    }
+}
+
+/// Frequency measurement gate, `SENSe:FREQuency:GATE` subsystem.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GateSetup {
+    pub source: GateSource,
+    /// Gate time, used with `GateSource::Time`
+    pub time: Duration,
+    pub output: GateOutput,
+}
+
+impl Default for GateSetup {
+    fn default() -> Self {
+        Self {
+            source: GateSource::Time,
+            time: Duration::from_millis(100),
+            output: GateOutput::default(),
+        }
+    }
+}
+
+/// `TRIGger` subsystem.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TriggerSetup {
+    pub source: TriggerSource,
+    /// Edge of external trigger signal
+    pub slope: Slope,
+    pub count: usize,
+    pub delay: Duration,
+}
+
+impl Default for TriggerSetup {
+    fn default() -> Self {
+        Self {
+            source: TriggerSource::Immediate,
+            slope: Slope::Negative,
+            count: 1,
+            delay: Duration::from_secs(0),
+        }
+    }
+}
+
+/// `CALCulate` subsystem.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CalcSetup {
+    /// Math processing
+    pub enabled: bool,
+    /// Statistics, takes effect when math is enabled
+    pub average: bool,
+}
+
+/// Complete measurement configuration.
+///
+/// `Default` matches the device state after `*RST`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CounterSetup {
+    pub function: Function,
+    /// Settings of channels 1 and 2, only channels used by function are applied
+    pub inputs: [InputSetup; 2],
+    pub gate: GateSetup,
+    pub trigger: TriggerSetup,
+    pub sample_count: usize,
+    pub calc: CalcSetup,
+    pub rosc: RoscSource,
+}
+
+impl Default for CounterSetup {
+    fn default() -> Self {
+        Self {
+            function: Function::Frequency(ChannelNo::Ch1),
+            inputs: [InputSetup::default(), InputSetup::default()],
+            gate: GateSetup::default(),
+            trigger: TriggerSetup::default(),
+            sample_count: 1,
+            calc: CalcSetup::default(),
+            rosc: RoscSource::Auto,
+        }
+    }
+}
+
+/// Setting whose value on device differs from the expected one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    /// Setting header, e.g. `INP1:LEV`
+    pub setting: String,
+    pub expected: String,
+    pub actual: String,
+    commands: Vec<String>,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: expected {}, actual {}", self.setting, self.expected, self.actual)
+    }
+}
+
+impl Difference {
+    /// Commands restoring the expected value.
+    pub fn commands(&self) -> &[String] {
+        &self.commands
+    }
+}
+
+/// Collects settings in the order they should be sent.
+///
+/// When `compare` is set only settings differing from actual ones are kept.
+struct Changes {
+    compare: bool,
+    list: Vec<Difference>,
+}
+
+impl Changes {
+    fn push<T: fmt::Debug>(&mut self, setting: String, expected: &T, actual: &T, same: bool, commands: Vec<String>) {
+        if !self.compare || !same {
+            self.list.push(Difference {
+                setting,
+                expected: format!("{:?}", expected),
+                actual: format!("{:?}", actual),
+                commands,
+            });
+        }
+    }
+}
+
+/// Read back values are rounded by device, so floats are compared with tolerance.
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-6*a.abs().max(b.abs())
+}
+
+fn close_dur(a: Duration, b: Duration) -> bool {
+    close(dur_as_secs(a), dur_as_secs(b))
+}
+
+impl InputSetup {
+    fn changes(&self, actual: &Self, cn: ChannelNo, ch: &mut Changes) {
+        let n = cn as u8;
+        // Impedance limits range and range limits level, so the order matters.
+        ch.push(
+            format!("INP{}:IMP", n), &self.impedance, &actual.impedance,
+            self.impedance == actual.impedance,
+            vec![format!("INP{}:IMP {}", n, self.impedance.ohms())],
+        );
+        ch.push(
+            format!("INP{}:RANG", n), &self.range, &actual.range,
+            close(self.range, actual.range),
+            vec![format!("INP{}:RANG {}", n, self.range)],
+        );
+        ch.push(
+            format!("INP{}:COUP", n), &self.coupling, &actual.coupling,
+            self.coupling == actual.coupling,
+            vec![format!("INP{}:COUP {}", n, self.coupling.mnemonic())],
+        );
+        ch.push(
+            format!("INP{}:FILT", n), &self.filter, &actual.filter,
+            self.filter == actual.filter,
+            vec![format!("INP{}:FILT {}", n, bool_as_str(self.filter))],
+        );
+        ch.push(
+            format!("INP{}:NREJ", n), &self.noise_reject, &actual.noise_reject,
+            self.noise_reject == actual.noise_reject,
+            vec![format!("INP{}:NREJ {}", n, bool_as_str(self.noise_reject))],
+        );
+        ch.push(
+            format!("INP{}:SLOP", n), &self.slope, &actual.slope,
+            self.slope == actual.slope,
+            vec![format!("INP{}:SLOP {}", n, self.slope.mnemonic())],
+        );
+        let (same, cmds) = match (self.level, actual.level) {
+            (Some(level), actual) => (
+                actual.is_some_and(|a| close(level, a)),
+                vec![format!("INP{}:LEV:AUTO OFF", n), format!("INP{}:LEV {}", n, level)],
+            ),
+            (None, actual) => (
+                actual.is_none(),
+                vec![format!("INP{}:LEV:AUTO ON", n)],
+            ),
+        };
+        ch.push(format!("INP{}:LEV", n), &self.level, &actual.level, same, cmds);
+    }
+}
+
+impl GateSetup {
+    fn changes(&self, actual: &Self, ch: &mut Changes) {
+        ch.push(
+            String::from("FREQ:GATE:SOUR"), &self.source, &actual.source,
+            self.source == actual.source,
+            vec![format!("FREQ:GATE:SOUR {}", self.source.mnemonic())],
+        );
+        if let GateSource::Time = self.source {
+            ch.push(
+                String::from("FREQ:GATE:TIME"), &self.time, &actual.time,
+                close_dur(self.time, actual.time),
+                vec![format!("FREQ:GATE:TIME {}", dur_as_secs(self.time))],
+            );
+        }
+        ch.push(
+            String::from("OUTP:POL"), &self.output.polarity, &actual.output.polarity,
+            self.output.polarity == actual.output.polarity,
+            vec![format!("OUTP:POL {}", self.output.polarity.mnemonic())],
+        );
+        ch.push(
+            String::from("OUTP:STAT"), &self.output.enabled, &actual.output.enabled,
+            self.output.enabled == actual.output.enabled,
+            vec![format!("OUTP:STAT {}", bool_as_str(self.output.enabled))],
+        );
+    }
+}
+
+impl TriggerSetup {
+    fn changes(&self, actual: &Self, ch: &mut Changes) {
+        ch.push(
+            String::from("TRIG:SOUR"), &self.source, &actual.source,
+            self.source == actual.source,
+            vec![format!("TRIG:SOUR {}", self.source.mnemonic())],
+        );
+        ch.push(
+            String::from("TRIG:SLOP"), &self.slope, &actual.slope,
+            self.slope == actual.slope,
+            vec![format!("TRIG:SLOP {}", self.slope.mnemonic())],
+        );
+        ch.push(
+            String::from("TRIG:COUN"), &self.count, &actual.count,
+            self.count == actual.count,
+            vec![format!("TRIG:COUN {}", self.count)],
+        );
+        ch.push(
+            String::from("TRIG:DEL"), &self.delay, &actual.delay,
+            close_dur(self.delay, actual.delay),
+            vec![format!("TRIG:DEL {}", dur_as_secs(self.delay))],
+        );
+    }
+}
+
+impl CalcSetup {
+    fn changes(&self, actual: &Self, ch: &mut Changes) {
+        ch.push(
+            String::from("CALC:STAT"), &self.enabled, &actual.enabled,
+            self.enabled == actual.enabled,
+            vec![format!("CALC:STAT {}", bool_as_str(self.enabled))],
+        );
+        if self.enabled {
+            ch.push(
+                String::from("CALC:AVER:STAT"), &self.average, &actual.average,
+                self.average == actual.average,
+                vec![format!("CALC:AVER:STAT {}", bool_as_str(self.average))],
+            );
+        }
+    }
+}
+
+fn rosc_commands(rosc: RoscSource) -> Vec<String> {
+    match rosc {
+        RoscSource::Auto => vec![String::from("ROSC:SOUR:AUTO ON")],
+        RoscSource::Internal => vec![
+            String::from("ROSC:SOUR:AUTO OFF"),
+            String::from("ROSC:SOUR INT"),
+        ],
+        RoscSource::External => vec![
+            String::from("ROSC:SOUR:AUTO OFF"),
+            String::from("ROSC:SOUR EXT"),
+        ],
+    }
+}
+
+impl CounterSetup {
+    pub fn input(&self, cn: ChannelNo) -> &InputSetup {
+        &self.inputs[cn as usize - 1]
+    }
+    pub fn input_mut(&mut self, cn: ChannelNo) -> &mut InputSetup {
+        &mut self.inputs[cn as usize - 1]
+    }
+
+    fn changes(&self, actual: &Self, ch: &mut Changes) {
+        ch.push(
+            String::from("FUNC"), &self.function, &actual.function,
+            self.function == actual.function,
+            vec![format!("FUNC \"{}\"", self.function.as_param())],
+        );
+        for cn in self.function.channels() {
+            self.input(cn).changes(actual.input(cn), cn, ch);
+        }
+        self.gate.changes(&actual.gate, ch);
+        self.trigger.changes(&actual.trigger, ch);
+        ch.push(
+            String::from("SAMP:COUN"), &self.sample_count, &actual.sample_count,
+            self.sample_count == actual.sample_count,
+            vec![format!("SAMP:COUN {}", self.sample_count)],
+        );
+        self.calc.changes(&actual.calc, ch);
+        ch.push(
+            String::from("ROSC:SOUR"), &self.rosc, &actual.rosc,
+            self.rosc == actual.rosc,
+            rosc_commands(self.rosc),
+        );
+    }
+
+    /// Commands establishing the setup, in the order they should be sent.
+    pub fn commands(&self) -> Vec<String> {
+        let mut ch = Changes { compare: false, list: Vec::new() };
+        self.changes(self, &mut ch);
+        ch.list.into_iter().flat_map(|d| d.commands).collect()
+    }
+
+    /// Settings of `actual` setup differing from this one.
+    ///
+    /// Settings unused by the function, e.g. inputs of other channel, are ignored.
+    pub fn diff(&self, actual: &CounterSetup) -> Vec<Difference> {
+        let mut ch = Changes { compare: true, list: Vec::new() };
+        self.changes(actual, &mut ch);
+        ch.list
+    }
+}
+
+/// Error the device reports when gate output is combined with external gating.
+fn ext_gate_conflict() -> crate::Error {
+    crate::Error::Dev(KsDevErr::from(
+        KsDevErrVariant::SettingsConflict(ConflictKind::ExtGateOutput)
+    ))
+}
+
+struct InputSlots {
+    coupling: Slot<Coupling>,
+    impedance: Slot<f64>,
+    range: Slot<f64>,
+    level_auto: Slot<bool>,
+    level: Slot<f64>,
+    slope: Slot<Slope>,
+    filter: Slot<bool>,
+    noise_reject: Slot<bool>,
+}
+
+impl KsFc {
+    /// Applies measurement setup.
+    ///
+    /// Current settings are read first and only the differing ones are sent
+    /// in a single batch.
+    pub fn apply(&mut self, setup: &CounterSetup) -> crate::Result<()> {
+        check_readings(setup.trigger.count, setup.sample_count)
+        .map_err(|e| Self::rejected(&format!("SAMP:COUN {}", setup.sample_count), e))?;
+        if setup.gate.output.enabled && setup.gate.source == GateSource::External {
+            return Err(Self::rejected("OUTP:STAT ON", ext_gate_conflict()));
+        }
+        self.restore_setup(setup).map(|_| ())
+    }
+
+    /// Compares device settings with `expected` setup.
+    pub fn verify_setup(&mut self, expected: &CounterSetup) -> crate::Result<Vec<Difference>> {
+        self.read_setup().map(|actual| expected.diff(&actual))
+    }
+
+    /// Re-applies settings which differ from `expected` setup.
+    ///
+    /// Returns differences found, empty when the device was left untouched.
+    pub fn restore_setup(&mut self, expected: &CounterSetup) -> crate::Result<Vec<Difference>> {
+        let diff = self.verify_setup(expected)?;
+        if !diff.is_empty() {
+            let mut batch = self.batch();
+            for cmd in diff.iter().flat_map(|d| d.commands.iter()) {
+                batch.command(cmd);
+            }
+            batch.send()?;
+        }
+        Ok(diff)
+    }
+
+    /// Reads current measurement setup from device.
+    pub fn read_setup(&mut self) -> crate::Result<CounterSetup> {
+        self.idempotent(|fc| {
+            let mut batch = fc.batch();
+            let function = batch.query::<Function>("FUNC?");
+            let [slots1, slots2] = [ChannelNo::Ch1, ChannelNo::Ch2].map(|cn| {
+                let n = cn as u8;
+                InputSlots {
+                    coupling: batch.query(&format!("INP{}:COUP?", n)),
+                    impedance: batch.query_with(&format!("INP{}:IMP?", n), parse_float),
+                    range: batch.query_with(&format!("INP{}:RANG?", n), parse_float),
+                    level_auto: batch.query_with(&format!("INP{}:LEV:AUTO?", n), parse_flag),
+                    level: batch.query_with(&format!("INP{}:LEV?", n), parse_float),
+                    slope: batch.query(&format!("INP{}:SLOP?", n)),
+                    filter: batch.query_with(&format!("INP{}:FILT?", n), parse_flag),
+                    noise_reject: batch.query_with(&format!("INP{}:NREJ?", n), parse_flag),
+                }
+            });
+            let gate_source = batch.query::<GateSource>("FREQ:GATE:SOUR?");
+            let gate_time = batch.query_with("FREQ:GATE:TIME?", parse_secs);
+            let output_state = batch.query_with("OUTP:STAT?", parse_flag);
+            let output_polarity = batch.query::<Polarity>("OUTP:POL?");
+            let trig_source = batch.query::<TriggerSource>("TRIG:SOUR?");
+            let trig_slope = batch.query::<Slope>("TRIG:SLOP?");
+            let trig_count = batch.query::<usize>("TRIG:COUN?");
+            let trig_delay = batch.query_with("TRIG:DEL?", parse_secs);
+            let sample_count = batch.query::<usize>("SAMP:COUN?");
+            let calc = batch.query_with("CALC:STAT?", parse_flag);
+            let average = batch.query_with("CALC:AVER:STAT?", parse_flag);
+            let rosc_auto = batch.query_with("ROSC:SOUR:AUTO?", parse_flag);
+            let rosc_ext = batch.query_with("ROSC:SOUR?", |text| {
+                parse_mnemonic(text, &[("INT", false), ("EXT", true)]).map_err(|e| e.into())
+            });
+            let reply = batch.send()?;
+
+            let read_input = |slots: InputSlots| -> crate::Result<InputSetup> {
+                Ok(InputSetup {
+                    coupling: reply.get(slots.coupling)?,
+                    impedance: Impedance::from_ohms(reply.get(slots.impedance)?),
+                    range: reply.get(slots.range)?,
+                    level: if reply.get(slots.level_auto)? {
+                        None
+                    } else {
+                        Some(reply.get(slots.level)?)
+                    },
+                    slope: reply.get(slots.slope)?,
+                    filter: reply.get(slots.filter)?,
+                    noise_reject: reply.get(slots.noise_reject)?,
+                })
+            };
+            let setup = CounterSetup {
+                function: reply.get(function)?,
+                inputs: [read_input(slots1)?, read_input(slots2)?],
+                gate: GateSetup {
+                    source: reply.get(gate_source)?,
+                    time: reply.get(gate_time)?,
+                    output: GateOutput {
+                        enabled: reply.get(output_state)?,
+                        polarity: reply.get(output_polarity)?,
+                    },
+                },
+                trigger: TriggerSetup {
+                    source: reply.get(trig_source)?,
+                    slope: reply.get(trig_slope)?,
+                    count: reply.get(trig_count)?,
+                    delay: reply.get(trig_delay)?,
+                },
+                sample_count: reply.get(sample_count)?,
+                calc: CalcSetup {
+                    enabled: reply.get(calc)?,
+                    average: reply.get(average)?,
+                },
+                rosc: if reply.get(rosc_auto)? {
+                    RoscSource::Auto
+                } else if reply.get(rosc_ext)? {
+                    RoscSource::External
+                } else {
+                    RoscSource::Internal
+                },
+            };
+            Ok(setup)
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands() {
+        let cmds = CounterSetup::default().commands();
+        assert_eq!(cmds[0], "FUNC \"FREQ 1\"");
+        assert!(cmds.iter().any(|c| c == "INP1:LEV:AUTO ON"));
+        assert!(!cmds.iter().any(|c| c.starts_with("INP2")));
+        assert_eq!(cmds.last().unwrap(), "ROSC:SOUR:AUTO ON");
+    }
+
+    #[test]
+    fn diff() {
+        let expected = CounterSetup::default();
+        let mut actual = expected.clone();
+        assert!(expected.diff(&actual).is_empty());
+
+        actual.input_mut(ChannelNo::Ch2).range = 50.0;
+        actual.gate.time = Duration::from_nanos(100_000_001);
+        assert!(expected.diff(&actual).is_empty());
+
+        actual.input_mut(ChannelNo::Ch1).level = Some(1.0);
+        actual.trigger.count = 10;
+        let diff = expected.diff(&actual);
+        assert_eq!(diff.len(), 2);
+        assert_eq!(diff[0].setting, "INP1:LEV");
+        assert_eq!(diff[0].commands(), ["INP1:LEV:AUTO ON"]);
+        assert_eq!(diff[1].commands(), ["TRIG:COUN 1"]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let mut setup = CounterSetup {
+            function: Function::TimeInterval(ChannelNo::Ch1, ChannelNo::Ch2),
+            rosc: RoscSource::External,
+            ..CounterSetup::default()
+        };
+        setup.input_mut(ChannelNo::Ch2).level = Some(-0.25);
+        setup.trigger.delay = Duration::from_millis(15);
+        let text = serde_json::to_string(&setup).unwrap();
+        assert_eq!(serde_json::from_str::<CounterSetup>(&text).unwrap(), setup);
+    }
+}
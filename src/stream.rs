@@ -0,0 +1,80 @@
+use std::time::{Duration, Instant};
+use std::thread::{sleep};
+
+use crate::{KsFc};
+use crate::constants::*;
+
+
+/// Reads measurement results from reading memory as they arrive.
+///
+/// Yields non-empty chunks of readings until `expected` readings are
+/// received, see `KsFc::stream`.
+pub struct Stream<'a> {
+    fc: &'a mut KsFc,
+    remaining: usize,
+    timeout: Duration,
+}
+
+impl<'a> Stream<'a> {
+    /// Number of readings not received yet.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    fn next_chunk(&mut self) -> crate::Result<Vec<f64>> {
+        let start = Instant::now();
+        loop {
+            let chunk = self.fc.r(Some(self.remaining))?;
+            if !chunk.is_empty() {
+                self.remaining -= chunk.len().min(self.remaining);
+                break Ok(chunk);
+            }
+            let elapsed = start.elapsed();
+            if elapsed >= self.timeout {
+                let err = crate::Error::WaitTimeout {
+                    event: String::from("New readings"),
+                    waited: elapsed,
+                };
+                let cmd = format!("R? {}", self.remaining);
+                break Err(err.with_context(KsFc::context(&cmd, start, None)));
+            }
+            sleep(STB_POLL_PERIOD.min(self.timeout - elapsed));
+        }
+    }
+}
+
+impl<'a> Iterator for Stream<'a> {
+    type Item = crate::Result<Vec<f64>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let res = self.next_chunk();
+        if res.is_err() {
+            self.remaining = 0;
+        }
+        Some(res)
+    }
+}
+
+impl KsFc {
+    /// Streams readings of the measurement in progress.
+    ///
+    /// Expects trigger count × sample count readings, which must not exceed
+    /// `MAX_READINGS`. Fails with `Error::WaitTimeout` when no new readings
+    /// arrive within `timeout`.
+    pub fn stream(&mut self, timeout: Duration) -> crate::Result<Stream<'_>> {
+        let remaining = self.checked_reading_count("R?")?;
+        Ok(Stream { fc: self, remaining, timeout })
+    }
+
+    /// Waits for all readings of the measurement in progress.
+    pub fn read_all(&mut self, timeout: Duration) -> crate::Result<Vec<f64>> {
+        let mut readings = Vec::new();
+        for chunk in self.stream(timeout)? {
+            readings.extend(chunk?);
+        }
+        Ok(readings)
+    }
+}
@@ -1,4 +1,14 @@
+use std::fmt;
+use std::str::{FromStr};
+use std::convert::{TryFrom};
+
 use enum_map::{Enum};
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use crate::KsDevErr;
+use crate::deverr::{KsDevErrVariant, RangeKind};
+use crate::format::{ParseError, parse, parse_mnemonic};
 
 bitflags! {
     pub struct EventReg: u8 {
@@ -11,15 +21,500 @@ bitflags! {
     }
 }
 
+bitflags! {
+    pub struct StatusByte: u8 {
+        const ERR_QUEUE      = 0b00000100;
+        const QUES_SUMMARY   = 0b00001000;
+        const MSG_AVAIL      = 0b00010000;
+        const EVENT_SUMMARY  = 0b00100000;
+        const MASTER_SUMMARY = 0b01000000;
+        const OPER_SUMMARY   = 0b10000000;
+    }
+}
+
+bitflags! {
+    pub struct OperationReg: u16 {
+        const CALIBRATING   = 0x0001;
+        const MEASURING     = 0x0010;
+        const WAIT_TRIGGER  = 0x0020;
+        const CONFIG_CHANGE = 0x0100;
+        const MEM_THRESHOLD = 0x0200;
+        const INSTR_LOCKED  = 0x0400;
+    }
+}
+
+bitflags! {
+    pub struct QuestionableReg: u16 {
+        const OVERLOAD      = 0x0001;
+        const TIMEOUT       = 0x0004;
+        const ROSC_UNLOCKED = 0x0020;
+        const CALIBRATION   = 0x0100;
+        const LIMIT_FAIL_LO = 0x0800;
+        const LIMIT_FAIL_HI = 0x1000;
+        const MEM_OVERFLOW  = 0x4000;
+        const LIMIT_FAIL    = Self::LIMIT_FAIL_LO.bits | Self::LIMIT_FAIL_HI.bits;
+    }
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Enum)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ChannelNo {
     Ch1 = 1,
     Ch2 = 2,
 }
 
+impl FromStr for ChannelNo {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let text = text.trim();
+        let text = text.trim_start_matches("(@").trim_end_matches(')');
+        parse_mnemonic(text, &[("1", ChannelNo::Ch1), ("2", ChannelNo::Ch2)])
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TriggerSource {
     Immediate,
     External,
     Bus,
 }
+
+impl TriggerSource {
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            TriggerSource::Immediate => "IMM",
+            TriggerSource::External => "EXT",
+            TriggerSource::Bus => "BUS",
+        }
+    }
+}
+
+impl FromStr for TriggerSource {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        parse_mnemonic(text, &[
+            ("IMM", TriggerSource::Immediate),
+            ("EXT", TriggerSource::External),
+            ("BUS", TriggerSource::Bus),
+        ])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Slope {
+    Positive,
+    Negative,
+}
+
+impl Slope {
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Slope::Positive => "POS",
+            Slope::Negative => "NEG",
+        }
+    }
+}
+
+impl FromStr for Slope {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        parse_mnemonic(text, &[("POS", Slope::Positive), ("NEG", Slope::Negative)])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Coupling {
+    Ac,
+    Dc,
+}
+
+impl Coupling {
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Coupling::Ac => "AC",
+            Coupling::Dc => "DC",
+        }
+    }
+}
+
+impl FromStr for Coupling {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        parse_mnemonic(text, &[("AC", Coupling::Ac), ("DC", Coupling::Dc)])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Impedance {
+    /// 50 Ohm
+    Low,
+    /// 1 MOhm
+    High,
+}
+
+impl Impedance {
+    pub fn ohms(&self) -> f64 {
+        match self {
+            Impedance::Low => 50.0,
+            Impedance::High => 1e6,
+        }
+    }
+    pub fn from_ohms(ohms: f64) -> Self {
+        if ohms < 1e3 {
+            Impedance::Low
+        } else {
+            Impedance::High
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GateSource {
+    Time,
+    External,
+    Advanced,
+}
+
+impl GateSource {
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            GateSource::Time => "TIME",
+            GateSource::External => "EXT",
+            GateSource::Advanced => "ADV",
+        }
+    }
+}
+
+impl FromStr for GateSource {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        parse_mnemonic(text, &[
+            ("TIME", GateSource::Time),
+            ("EXT", GateSource::External),
+            ("ADV", GateSource::Advanced),
+        ])
+    }
+}
+
+/// Gate output signal polarity, `OUTPut:POLarity`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Polarity {
+    Normal,
+    Inverted,
+}
+
+impl Polarity {
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Polarity::Normal => "NORM",
+            Polarity::Inverted => "INV",
+        }
+    }
+}
+
+impl FromStr for Polarity {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        parse_mnemonic(text, &[("NORM", Polarity::Normal), ("INV", Polarity::Inverted)])
+    }
+}
+
+/// Reference oscillator source
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RoscSource {
+    Internal,
+    External,
+    /// External if valid signal is present, internal otherwise
+    Auto,
+}
+
+/// Measurement function with its channels
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Function {
+    Frequency(ChannelNo),
+    Period(ChannelNo),
+    SinglePeriod(ChannelNo),
+    FrequencyRatio(ChannelNo, ChannelNo),
+    TimeInterval(ChannelNo, ChannelNo),
+    Phase(ChannelNo, ChannelNo),
+    TotalizeTimed(ChannelNo),
+    TotalizeContinuous(ChannelNo),
+    PositiveWidth(ChannelNo),
+    NegativeWidth(ChannelNo),
+    PositiveDuty(ChannelNo),
+    NegativeDuty(ChannelNo),
+    RiseTime(ChannelNo),
+    FallTime(ChannelNo),
+}
+
+impl Function {
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Function::Frequency(..) => "FREQ",
+            Function::Period(..) => "PER",
+            Function::SinglePeriod(..) => "SPER",
+            Function::FrequencyRatio(..) => "FREQ:RAT",
+            Function::TimeInterval(..) => "TINT",
+            Function::Phase(..) => "PHAS",
+            Function::TotalizeTimed(..) => "TOT:TIM",
+            Function::TotalizeContinuous(..) => "TOT:CONT",
+            Function::PositiveWidth(..) => "PWID",
+            Function::NegativeWidth(..) => "NWID",
+            Function::PositiveDuty(..) => "PDUT",
+            Function::NegativeDuty(..) => "NDUT",
+            Function::RiseTime(..) => "RTIM",
+            Function::FallTime(..) => "FTIM",
+        }
+    }
+
+    /// Channels used by the function.
+    pub fn channels(&self) -> Vec<ChannelNo> {
+        match *self {
+            Function::FrequencyRatio(a, b) |
+            Function::TimeInterval(a, b) |
+            Function::Phase(a, b) => vec![a, b],
+            Function::Frequency(a) |
+            Function::Period(a) |
+            Function::SinglePeriod(a) |
+            Function::TotalizeTimed(a) |
+            Function::TotalizeContinuous(a) |
+            Function::PositiveWidth(a) |
+            Function::NegativeWidth(a) |
+            Function::PositiveDuty(a) |
+            Function::NegativeDuty(a) |
+            Function::RiseTime(a) |
+            Function::FallTime(a) => vec![a],
+        }
+    }
+
+    /// Function parameter of `SENSe:FUNCtion`, e.g. `FREQ:RAT 1,2`.
+    pub fn as_param(&self) -> String {
+        let chs: Vec<String> = self.channels().iter().map(|c| (*c as u8).to_string()).collect();
+        format!("{} {}", self.mnemonic(), chs.join(","))
+    }
+}
+
+impl FromStr for Function {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let text = text.trim().trim_matches('"');
+        let mut parts = text.splitn(2, ' ');
+        let name = parts.next().ok_or(ParseError::EndOfString)?.to_ascii_uppercase();
+        let chs = parts.next().ok_or(ParseError::EndOfString)?
+        .split(',')
+        .map(|c| c.parse::<ChannelNo>())
+        .collect::<Result<Vec<_>, _>>()?;
+        let one = || chs.first().cloned().ok_or(ParseError::EndOfString);
+        let two = || chs.get(1).cloned().ok_or(ParseError::EndOfString);
+        Ok(match name.as_str() {
+            "FREQ" | "FREQUENCY" => Function::Frequency(one()?),
+            "PER" | "PERIOD" => Function::Period(one()?),
+            "SPER" | "SPERIOD" => Function::SinglePeriod(one()?),
+            "FREQ:RAT" | "FREQUENCY:RATIO" => Function::FrequencyRatio(one()?, two()?),
+            "TINT" | "TINTERVAL" => Function::TimeInterval(one()?, two()?),
+            "PHAS" | "PHASE" => Function::Phase(one()?, two()?),
+            "TOT:TIM" | "TOTALIZE:TIMED" => Function::TotalizeTimed(one()?),
+            "TOT:CONT" | "TOTALIZE:CONTINUOUS" => Function::TotalizeContinuous(one()?),
+            "PWID" | "PWIDTH" => Function::PositiveWidth(one()?),
+            "NWID" | "NWIDTH" => Function::NegativeWidth(one()?),
+            "PDUT" | "PDUTYCYCLE" => Function::PositiveDuty(one()?),
+            "NDUT" | "NDUTYCYCLE" => Function::NegativeDuty(one()?),
+            "RTIM" | "RTIME" => Function::RiseTime(one()?),
+            "FTIM" | "FTIME" => Function::FallTime(one()?),
+            _ => return Err(ParseError::Value(name)),
+        })
+    }
+}
+
+/// Front panel display mode, `DISPlay:MODE`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DisplayMode {
+    Numeric,
+    Histogram,
+    TrendChart,
+}
+
+impl DisplayMode {
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            DisplayMode::Numeric => "NUM",
+            DisplayMode::Histogram => "HIST",
+            DisplayMode::TrendChart => "TCH",
+        }
+    }
+}
+
+impl FromStr for DisplayMode {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        parse_mnemonic(text, &[
+            ("NUM", DisplayMode::Numeric),
+            ("HIST", DisplayMode::Histogram),
+            ("TCH", DisplayMode::TrendChart),
+        ])
+    }
+}
+
+/// Remote interface, `SYSTem:COMMunicate:ENABle` argument.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Interface {
+    Gpib,
+    Usb,
+    Lan,
+    Sockets,
+    Telnet,
+    Vxi11,
+    Web,
+}
+
+impl Interface {
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Interface::Gpib => "GPIB",
+            Interface::Usb => "USB",
+            Interface::Lan => "LAN",
+            Interface::Sockets => "SOCK",
+            Interface::Telnet => "TELN",
+            Interface::Vxi11 => "VXI11",
+            Interface::Web => "WEB",
+        }
+    }
+}
+
+/// Screen capture image format, `HCOPy:SDUMp:DATA:FORMat`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ImageFormat {
+    Png,
+    Bmp,
+}
+
+impl ImageFormat {
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "PNG",
+            ImageFormat::Bmp => "BMP",
+        }
+    }
+
+    /// Leading bytes of image file.
+    pub fn signature(&self) -> &'static [u8] {
+        match self {
+            ImageFormat::Png => b"\x89PNG\r\n\x1a\n",
+            ImageFormat::Bmp => b"BM",
+        }
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        parse_mnemonic(text, &[("PNG", ImageFormat::Png), ("BMP", ImageFormat::Bmp)])
+    }
+}
+
+/// Instrument state storage location, `*SAV`/`*RCL` argument.
+///
+/// Location 0 holds the state saved at power-down.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StateSlot(u8);
+
+impl StateSlot {
+    pub const COUNT: u8 = 5;
+    pub const POWER_DOWN: StateSlot = StateSlot(0);
+
+    pub fn number(&self) -> u8 {
+        self.0
+    }
+
+    /// All locations in ascending order.
+    pub fn all() -> impl Iterator<Item=StateSlot> {
+        (0..Self::COUNT).map(StateSlot)
+    }
+}
+
+impl TryFrom<u8> for StateSlot {
+    type Error = crate::Error;
+    fn try_from(n: u8) -> crate::Result<Self> {
+        if n < Self::COUNT {
+            Ok(StateSlot(n))
+        } else {
+            Err(crate::Error::Dev(KsDevErr::from(
+                KsDevErrVariant::DataOutOfRange(RangeKind::Generic)
+            )))
+        }
+    }
+}
+
+impl FromStr for StateSlot {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let n = parse::<u8>(text.trim())?;
+        StateSlot::try_from(n).map_err(|_| ParseError::Value(text.to_string()))
+    }
+}
+
+/// Instrument identification, `*IDN?` reply.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Identity {
+    pub manufacturer: String,
+    pub model: String,
+    pub serial: String,
+    pub firmware: String,
+}
+
+impl FromStr for Identity {
+    type Err = ParseError;
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let mut parts = text.trim().splitn(4, ',').map(|s| String::from(s.trim()));
+        let mut next = || parts.next().ok_or(ParseError::TooFewArgs);
+        Ok(Identity {
+            manufacturer: next()?,
+            model: next()?,
+            serial: next()?,
+            firmware: next()?,
+        })
+    }
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{},{},{}", self.manufacturer, self.model, self.serial, self.firmware)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity() {
+        let text = "Agilent Technologies,53220A,MY50001234,02.05-1519.666-1.19-4.15-127-155-35\n";
+        let idn = text.parse::<Identity>().unwrap();
+        assert_eq!(idn.manufacturer, "Agilent Technologies");
+        assert_eq!(idn.model, "53220A");
+        assert_eq!(idn.serial, "MY50001234");
+        assert_eq!(idn.to_string(), text.trim());
+        assert!("Agilent Technologies,53220A".parse::<Identity>().is_err());
+    }
+
+    #[test]
+    fn state_slot() {
+        assert_eq!(StateSlot::all().count(), StateSlot::COUNT as usize);
+        assert_eq!(" +4".parse::<StateSlot>().unwrap().number(), 4);
+        assert!("5".parse::<StateSlot>().is_err());
+    }
+}